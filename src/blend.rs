@@ -0,0 +1,59 @@
+//! 描画先の既存のピクセルと、半透明に混ぜて描画するためのDrawTargetのラッパーです。
+//!
+//! embedded_graphicsのDrawTargetは、描画済みのピクセルを読み出す方法を持たないため、
+//! 読み出せる描画先には、ReadPixelを実装してもらいます。
+use crate::eg;
+use crate::MixFn;
+
+use eg::prelude::*;
+
+/// 描画済みのピクセルの色を読み出せる描画先
+///
+/// Font7Seg::draw_string_blendedで、文字を半透明に重ねるのに使います。
+/// フレームバッファを持つ表示器などに実装してください。
+pub trait ReadPixel: DrawTarget {
+    /// 点pの色を返します。範囲外や、まだ描画していない点の場合はNoneを返します。
+    fn read_pixel(&self, p: Point) -> Option<Self::Color>;
+}
+
+/// 描画する各ピクセルを、描画先の既存の色とalphaの割合で混ぜて描画するラッパー
+///
+/// 既存の色が読み出せないピクセルは、そのままの色で描画する。
+/// 同じピクセルを何度も描画すると、そのたびに既存の色と混ぜる。
+pub(crate) struct Blended<'a, D: DrawTarget> {
+    target: &'a mut D,
+    alpha: u8,
+    mix: MixFn<D::Color>,
+}
+
+impl<'a, D: ReadPixel> Blended<'a, D> {
+    pub(crate) fn new(target: &'a mut D, alpha: u8, mix: MixFn<D::Color>) -> Self {
+        Self { target, alpha, mix }
+    }
+}
+
+impl<D: ReadPixel> Dimensions for Blended<'_, D> {
+    fn bounding_box(&self) -> eg::primitives::Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: ReadPixel> DrawTarget for Blended<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // 読み出しと書き込みを交互に行うため、一点ずつ描画する。
+        for Pixel(p, color) in pixels {
+            let color = match self.target.read_pixel(p) {
+                Some(existing) => (self.mix)(color, existing, self.alpha),
+                None => color,
+            };
+            self.target.draw_iter(core::iter::once(Pixel(p, color)))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! 設定の組み合わせを確かめてから、Font7Segを生成するビルダーです。
+//!
+//! Font7Seg::new()と各with_*メソッドは、どんな値でもそのまま受け付けます。
+//! Font7SegBuilderでは、セグメントの形に関わる設定をまとめて指定し、build()で
+//! セルの大きさに収まるかを確かめてから、Font7Segを生成します。
+use crate::eg;
+use crate::{Font7Seg, Font7SegError, SegmentShape};
+
+use eg::pixelcolor::PixelColor;
+use eg::prelude::*;
+
+/// 設定を確かめてから、Font7Segを生成するビルダー
+///
+/// セグメントの形に関わる設定は、このビルダーのwith_*メソッドで指定します。
+/// 色などの、形に関わらない設定は、build()で生成したFont7Segに続けて指定できます。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+/// # use font_7seg::{Font7Seg, Font7SegError};
+/// let font = Font7Seg::builder(Size::new(10, 20), BinaryColor::On)
+///     .with_line_width_rate(0.25)
+///     .build();
+/// assert!(font.is_ok());
+///
+/// // 横長のセルに太すぎるセグメントでは、A、G、Dのセグメントが重なってしまう
+/// let font = Font7Seg::builder(Size::new(20, 20), BinaryColor::On)
+///     .with_line_width_rate(0.5)
+///     .build();
+/// assert_eq!(font.err(), Some(Font7SegError::InvalidGeometry));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Font7SegBuilder<C> {
+    font: Font7Seg<C>,
+}
+
+impl<C: PixelColor> Font7SegBuilder<C> {
+    /// 数字のサイズと色を指定して、ビルダーを生成します。その他の設定は、初期値です。
+    pub const fn new(size: Size, text_color: C) -> Self {
+        Self {
+            font: Font7Seg::new(size, text_color),
+        }
+    }
+
+    /// セグメントの太さを、数字の幅に対する比率で指定します。
+    /// 値の扱いは、Font7Seg::with_line_width_rateと同じです。
+    pub fn with_line_width_rate(mut self, rate: f32) -> Self {
+        self.font = self.font.with_line_width_rate(rate);
+        self
+    }
+
+    /// 上下と左右のマージンを指定します。値の扱いは、Font7Seg::with_marginsと同じです。
+    pub fn with_margins(mut self, top: f32, left: f32) -> Self {
+        self.font = self.font.with_margins(top, left);
+        self
+    }
+
+    /// 小数点の幅を、数字の幅に対する比率で指定します。
+    /// 値の扱いは、Font7Seg::with_point_width_rateと同じです。
+    pub fn with_point_width_rate(mut self, rate: f32) -> Self {
+        self.font = self.font.with_point_width_rate(rate);
+        self
+    }
+
+    /// セグメントの間の隙間を指定します。値の扱いは、Font7Seg::with_segment_gapと同じです。
+    pub fn with_segment_gap(mut self, pixels: u32) -> Self {
+        self.font = self.font.with_segment_gap(pixels);
+        self
+    }
+
+    /// セグメントの両端の形を指定します。Font7Seg::with_segment_shapeと同じです。
+    pub fn with_segment_shape(mut self, shape: SegmentShape) -> Self {
+        self.font = self.font.with_segment_shape(shape);
+        self
+    }
+
+    /// 設定を確かめて、Font7Segを生成します。
+    ///
+    /// 数字のサイズがFont7Seg::MIN_SIZEより小さい場合は、Font7SegError::InvalidSizeを
+    /// 返します。マージンとセグメントの太さ、隙間がセルに収まらず、セグメントが重なるか
+    /// 消えてしまう場合は、Font7SegError::InvalidGeometryを返します。
+    pub fn build(self) -> Result<Font7Seg<C>, Font7SegError> {
+        self.font.validate()?;
+        Ok(self.font)
+    }
+}
@@ -0,0 +1,118 @@
+//! 一文字を、テキストの格子に描画する、テスト用の機能です。
+//!
+//! debug-renderフィーチャーを有効にすると使えます。表示器の無い環境でも、
+//! テストの出力に字形を表示して、目で確かめられます。
+use crate::eg;
+use crate::Font7Seg;
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use eg::pixelcolor::PixelColor;
+use eg::prelude::*;
+use eg::text::renderer::TextRenderer;
+use eg::text::Baseline;
+
+/// 描画したピクセルを、バッファの'#'に置き換えるDrawTarget
+///
+/// バッファは、一行width文字と改行を、上の行から順に並べたもの。
+/// 範囲外の点は描画しない。
+struct AsciiTarget<'a, C> {
+    buf: &'a mut [u8],
+    size: Size,
+    offset: Point,
+    color: PhantomData<C>,
+}
+
+impl<C> OriginDimensions for AsciiTarget<'_, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: PixelColor> DrawTarget for AsciiTarget<'_, C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        let stride = self.size.width as usize + 1;
+        for Pixel(p, _) in pixels {
+            let p = p - self.offset;
+            if area.contains(p) {
+                self.buf[p.y as usize * stride + p.x as usize] = b'#';
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: PixelColor> Font7Seg<C> {
+    /// 一文字を、点灯するピクセルを'#'、それ以外を空白とした格子として、bufに描画します。
+    ///
+    /// 格子は、measure_stringのbounding_boxの大きさで、各行の末尾に改行が付きます。
+    /// 必要なバッファの長さは、(幅 + 1) × 高さです。背景色と消灯したセグメントの色は
+    /// 描画せず、文字色で描くピクセルだけを'#'にします。
+    ///
+    /// <戻り値>
+    /// 描画した格子の文字列を返します。未対応の文字か、bufが短い場合はNoneを返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(5, 9), BinaryColor::On);
+    /// let mut buf = [0; 64];
+    /// let grid = font.render_to_ascii('4', &mut buf).unwrap();
+    /// let expected = concat!(
+    ///     "     \n",
+    ///     " # # \n",
+    ///     " # # \n",
+    ///     " # # \n",
+    ///     "  #  \n",
+    ///     "   # \n",
+    ///     "   # \n",
+    ///     "   # \n",
+    ///     "     \n",
+    /// );
+    /// assert_eq!(grid, expected);
+    /// ```
+    pub fn render_to_ascii<'a>(&self, c: char, buf: &'a mut [u8]) -> Option<&'a str> {
+        if !crate::is_supported(c) {
+            return None;
+        }
+        let mut text = [0; 4];
+        let text = c.encode_utf8(&mut text);
+        let font = Self {
+            background_color: None,
+            background_gradient: None,
+            off_color: None,
+            debug_cells: None,
+            ..*self
+        };
+        let bounds = font
+            .drawn_metrics(text, Point::zero(), Baseline::Top)
+            .bounding_box;
+        let Size { width, height } = bounds.size;
+        let stride = width as usize + 1;
+        let len = stride * height as usize;
+        let buf = buf.get_mut(..len)?;
+        buf.fill(b' ');
+        for row in buf.chunks_exact_mut(stride) {
+            row[stride - 1] = b'\n';
+        }
+        let mut target = AsciiTarget {
+            buf: &mut *buf,
+            size: bounds.size,
+            offset: bounds.top_left,
+            color: PhantomData,
+        };
+        match font.draw_string(text, Point::zero(), Baseline::Top, &mut target) {
+            Ok(_) => {}
+            Err(never) => match never {},
+        }
+        core::str::from_utf8(buf).ok()
+    }
+}
@@ -0,0 +1,456 @@
+//! 14セグメントLED風の英数字フォントです。
+//!
+//! 7セグメントの外周6本(A〜F)に加えて、左右に分かれた中央の横線(G1,G2)、
+//! 中央の縦線(I,L)、4本の斜め線(H,J,K,M)を使い、英大文字を表示します。
+//!
+//! 実装する文字は、"0123456789"、"ABCDEFGHIJKLMNOPQRSTUVWXYZ"、"-"と空白です。
+//! 英小文字は、大文字と同じ字形で表示します。他の文字を渡すと無視します。
+use crate::eg;
+use crate::transform::{CellMap, Transformed};
+use crate::{centered_offset, scale_ceil, Font7Seg};
+
+use eg::pixelcolor::PixelColor;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::text::renderer::{CharacterStyle, TextMetrics, TextRenderer};
+use eg::text::Baseline;
+
+/// 14セグメントLED風フォント
+///
+/// 大きさや色、比率の設定は、Font7Segと共通です。
+///
+/// 斜めのセグメントを使う文字も、12x20程度の大きさで読める形に描画します。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # use font_7seg::Font14Seg;
+/// let font = Font14Seg::new(Size::new(12, 20), BinaryColor::On);
+/// let draw = |text| {
+///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+///     // 斜め線は、中央で重なる
+///     display.set_allow_overdraw(true);
+///     Text::with_baseline(text, Point::zero(), font, Baseline::Top)
+///         .draw(&mut display)
+///         .unwrap();
+///     display
+/// };
+/// // 中央の横線は、左右の半分が中央でつながる
+/// draw("H").assert_pattern(&[
+///     "            ",
+///     "            ",
+///     " #        # ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " #        # ",
+///     "   ######   ",
+///     "    ####    ",
+///     " #        # ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " ##      ## ",
+///     " #        # ",
+/// ]);
+/// // 斜め線は、角から中央までを結ぶ
+/// draw("N").assert_pattern(&[
+///     "            ",
+///     "            ",
+///     " #        # ",
+///     " ####    ## ",
+///     " ####    ## ",
+///     " ## ##   ## ",
+///     " ## ##   ## ",
+///     " ## ##   ## ",
+///     " #   ##   # ",
+///     "     ##     ",
+///     "     ##     ",
+///     " #   ##   # ",
+///     " ##   ## ## ",
+///     " ##   ## ## ",
+///     " ##   ## ## ",
+///     " ##    #### ",
+///     " ##    #### ",
+///     " #        # ",
+/// ]);
+/// draw("X").assert_pattern(&[
+///     "            ",
+///     "            ",
+///     "            ",
+///     "   ##  ##   ",
+///     "   ##  ##   ",
+///     "    ####    ",
+///     "    ####    ",
+///     "    ####    ",
+///     "     ##     ",
+///     "     ##     ",
+///     "     ##     ",
+///     "     ##     ",
+///     "    ####    ",
+///     "    ####    ",
+///     "    ####    ",
+///     "   ##  ##   ",
+///     "   ##  ##   ",
+/// ]);
+/// ```
+///
+/// 幅や高さが0や1のような、セグメントが収まらない大きさでも、パニックせずに描画します。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, text::Text, pixelcolor::BinaryColor};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # use font_7seg::Font14Seg;
+/// for (width, height) in [(0, 0), (1, 1), (2, 2), (30, 1), (1, 30), (0, 20), (10, 0)] {
+///     let font = Font14Seg::new(Size::new(width, height), BinaryColor::On);
+///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+///     display.set_allow_overdraw(true);
+///     Text::new("NXM", Point::new(0, 40), font).draw(&mut display).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Font14Seg<C> {
+    base: Font7Seg<C>,
+}
+
+impl<C: PixelColor> Font14Seg<C> {
+    /// フォントオブジェクトを生成します。
+    /// * `size`       - 表示する文字のサイズ(ピクセル単位)
+    /// * `text_color` - 表示する文字の色
+    pub const fn new(size: Size, text_color: C) -> Self {
+        Self {
+            base: Font7Seg::new(size, text_color),
+        }
+    }
+
+    /// 現在の表示する文字のサイズを返します。
+    pub fn character_size(&self) -> Size {
+        self.base.character_size()
+    }
+
+    /// セグメントの太さを、文字の幅に対する比率で指定します。(初期値 0.2)
+    ///
+    /// 0.0〜0.5の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    pub fn with_line_width_rate(mut self, rate: f32) -> Self {
+        self.base = self.base.with_line_width_rate(rate);
+        self
+    }
+
+    /// 上下と左右のマージンを、文字の高さ・幅に対する比率で指定します。(初期値 共に0.05)
+    /// * `top`  - 上下のマージン(高さに対する比率)
+    /// * `left` - 左右のマージン(幅に対する比率)
+    ///
+    /// 0.0〜0.25の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    pub fn with_margins(mut self, top: f32, left: f32) -> Self {
+        self.base = self.base.with_margins(top, left);
+        self
+    }
+
+    /// 消灯しているセグメントを、薄く表示する色を指定します。(初期値 なし)
+    pub fn with_off_color(mut self, off_color: C) -> Self {
+        self.base = self.base.with_off_color(off_color);
+        self
+    }
+
+    /// 中央の横線の左半分(G1)、右半分(G2)
+    ///
+    /// 外側の端は7セグメントのGと同じ形にし、中央側の端は削らずに、中央で切る。
+    /// 右半分は、左半分の左右の鏡像。
+    fn draw_seg_g<D>(&self, right: bool, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let Size { width, height } = area.bounding_box().size;
+        let seg_height: u32 = scale_ceil(width, self.base.line_width_rate);
+        let seg_top = centered_offset(height, seg_height);
+        let seg_area = Rectangle::new(Point::new(0, seg_top), Size::new(width, seg_height));
+        let [tip, top_left, _, _, _, bottom_left] = self.base.hori_segment_points(seg_area, false);
+        let gap = self.base.segment_gap as i32;
+        let cut = (width as i32 - 1) / 2 - (gap + 1) / 2;
+        let (top, middle, bottom) = (top_left.y, tip.y, bottom_left.y);
+        let points = if right {
+            let mirror = |x: i32| width as i32 - 1 - x;
+            [
+                Point::new(mirror(cut), middle),
+                Point::new(mirror(cut), top),
+                Point::new(mirror(top_left.x), top),
+                Point::new(mirror(tip.x), middle),
+                Point::new(mirror(bottom_left.x), bottom),
+                Point::new(mirror(cut), bottom),
+            ]
+        } else {
+            [
+                tip,
+                top_left,
+                Point::new(cut, top),
+                Point::new(cut, middle),
+                Point::new(cut, bottom),
+                bottom_left,
+            ]
+        };
+        self.base
+            .draw_polygon(&points, self.base.segment_style(color), area)
+    }
+
+    /// 中央の縦線の上半分(I)、下半分(L)
+    fn draw_seg_center<D>(&self, lower: bool, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let Size { width, height } = area.bounding_box().size;
+        let seg_width: u32 = scale_ceil(width, self.base.line_width_rate);
+        let seg_left: i32 = centered_offset(width, seg_width);
+        let seg_height: u32 = height.div_ceil(2);
+        // 下半分は、上半分の上下の鏡像
+        let seg_top: i32 = if lower {
+            height as i32 - seg_height as i32
+        } else {
+            0
+        };
+        let seg_top_left = Point::new(seg_left, seg_top);
+        let seg_size = Size::new(seg_width, seg_height);
+        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
+        self.base
+            .draw_segment_vert(color, false, lower, &mut seg_target)
+    }
+
+    /// 斜め線。左右(right)と上下(lower)の組み合わせで、H,J,K,Mの4本を描く。
+    ///
+    /// 各斜め線は、外周の2本のセグメントの内側の角から、描画領域の中央までを結ぶ、
+    /// 平行四辺形で描く。太さ(横方向の幅)は、セグメントの太さのおよそ半分。
+    /// 左上のHを基準に、他の3本はその鏡像として描く。
+    fn draw_seg_diagonal<D>(
+        &self,
+        right: bool,
+        lower: bool,
+        color: C,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let Size { width, height } = area.bounding_box().size;
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let line_width: u32 = scale_ceil(width, self.base.line_width_rate);
+        // 中央の端が左右対称になるよう、幅との差が偶数になる太さにする。
+        let thickness = line_width.div_ceil(2).max(1);
+        let thickness = (thickness + width.saturating_sub(thickness) % 2) as i32;
+        let gap = self.base.segment_gap as i32;
+        // 各頂点は、ピクセルの中心に置く。
+        let outer = line_width as i32 + gap;
+        let top = outer;
+        let bottom = (height as i32 - 1) / 2;
+        let center_left = centered_offset(width, thickness as u32);
+        if bottom < top || center_left < outer {
+            return Ok(());
+        }
+        let mirror = |p: Point| {
+            let x = if right { width as i32 - 1 - p.x } else { p.x };
+            let y = if lower { height as i32 - 1 - p.y } else { p.y };
+            Point::new(x, y)
+        };
+        let corners = [
+            Point::new(outer, top),
+            Point::new(outer + thickness - 1, top),
+            Point::new(center_left + thickness - 1, bottom),
+            Point::new(center_left, bottom),
+        ]
+        .map(mirror);
+        let points = [
+            corners[0], corners[1], corners[1], corners[2], corners[3], corners[3],
+        ];
+        Font7Seg::fill_polygon(&points, color, None, 0, None, area)
+    }
+
+    /// seg_patで指定したセグメントを、colorで描画する。
+    fn draw_segments<D>(&self, seg_pat: u16, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 外周の6本は、7セグメントと共通
+        self.base
+            .draw_seg_pattern((seg_pat & 0b0011_1111) as u8, &[color; 7], area)?;
+        if seg_pat & SEG_G1 != 0 {
+            self.draw_seg_g(false, color, area)?;
+        }
+        if seg_pat & SEG_G2 != 0 {
+            self.draw_seg_g(true, color, area)?;
+        }
+        if seg_pat & SEG_H != 0 {
+            self.draw_seg_diagonal(false, false, color, area)?;
+        }
+        if seg_pat & SEG_I != 0 {
+            self.draw_seg_center(false, color, area)?;
+        }
+        if seg_pat & SEG_J != 0 {
+            self.draw_seg_diagonal(true, false, color, area)?;
+        }
+        if seg_pat & SEG_K != 0 {
+            self.draw_seg_diagonal(false, true, color, area)?;
+        }
+        if seg_pat & SEG_L != 0 {
+            self.draw_seg_center(true, color, area)?;
+        }
+        if seg_pat & SEG_M != 0 {
+            self.draw_seg_diagonal(true, true, color, area)?;
+        }
+        Ok(())
+    }
+
+    /// 文字を一文字描画する。
+    fn draw_char<D>(&self, seg_pat: u16, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let inner = self.base.inner_area(area.bounding_box().size);
+        let mut area = area.cropped(&inner);
+        if let Some(off_color) = self.base.off_color {
+            self.draw_segments(SEG_ALL, off_color, &mut area)?;
+        }
+        self.draw_segments(seg_pat, self.base.text_color, &mut area)
+    }
+}
+
+const SEG_G1: u16 = 1 << 6;
+const SEG_G2: u16 = 1 << 7;
+const SEG_H: u16 = 1 << 8;
+const SEG_I: u16 = 1 << 9;
+const SEG_J: u16 = 1 << 10;
+const SEG_K: u16 = 1 << 11;
+const SEG_L: u16 = 1 << 12;
+const SEG_M: u16 = 1 << 13;
+const SEG_ALL: u16 = 0b0011_1111_1111_1111;
+
+/// 0〜9の各数字のセグメントパターン
+///
+/// bit0〜5=A〜F, bit6=G1(中央左), bit7=G2(中央右), bit8=H(左上斜め), bit9=I(上中央),
+/// bit10=J(右上斜め), bit11=K(左下斜め), bit12=L(下中央), bit13=M(右下斜め)
+const DIGIT_PATS: [u16; 10] = [
+    0b0000_0000_0011_1111,
+    0b0000_0000_0000_0110,
+    0b0000_0000_1101_1011,
+    0b0000_0000_1100_1111,
+    0b0000_0000_1110_0110,
+    0b0000_0000_1110_1101,
+    0b0000_0000_1111_1101,
+    0b0000_0000_0000_0111,
+    0b0000_0000_1111_1111,
+    0b0000_0000_1110_1111,
+];
+
+/// A〜Zの各文字のセグメントパターン(ビットの割り当てはDIGIT_PATSと同じ)
+const ALPHA_PATS: [u16; 26] = [
+    0b0000_0000_1111_0111,
+    0b0001_0010_1000_1111,
+    0b0000_0000_0011_1001,
+    0b0001_0010_0000_1111,
+    0b0000_0000_1111_1001,
+    0b0000_0000_0111_0001,
+    0b0000_0000_1011_1101,
+    0b0000_0000_1111_0110,
+    0b0001_0010_0000_1001,
+    0b0000_0000_0001_1110,
+    0b0010_0100_0111_0000,
+    0b0000_0000_0011_1000,
+    0b0000_0101_0011_0110,
+    0b0010_0001_0011_0110,
+    0b0000_0000_0011_1111,
+    0b0000_0000_1111_0011,
+    0b0010_0000_0011_1111,
+    0b0010_0000_1111_0011,
+    0b0000_0000_1110_1101,
+    0b0001_0010_0000_0001,
+    0b0000_0000_0011_1110,
+    0b0000_1100_0011_0000,
+    0b0010_1000_0011_0110,
+    0b0010_1101_0000_0000,
+    0b0001_0101_0000_0000,
+    0b0000_1100_0000_1001,
+];
+
+/// 文字に対応するセグメントパターンを返す。未対応の文字はNone。
+fn seg_pattern(c: char) -> Option<u16> {
+    match c {
+        '0'..='9' => Some(DIGIT_PATS[c as usize - '0' as usize]),
+        'A'..='Z' => Some(ALPHA_PATS[c as usize - 'A' as usize]),
+        'a'..='z' => Some(ALPHA_PATS[c as usize - 'a' as usize]),
+        '-' => Some(SEG_G1 | SEG_G2),
+        ' ' => Some(0),
+        _ => None,
+    }
+}
+
+impl<C: PixelColor> CharacterStyle for Font14Seg<C> {
+    type Color = C;
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.base.set_text_color(text_color);
+    }
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.base.set_background_color(background_color);
+    }
+}
+
+impl<C: PixelColor> TextRenderer for Font14Seg<C> {
+    type Color = C;
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let size = self.base.size;
+        let mut cur_pos = pos - Point::new(0, self.base.baseline_offset(baseline));
+        for seg_pat in text.chars().filter_map(seg_pattern) {
+            let map = CellMap {
+                size,
+                shear: 0.0,
+                mirror_x: false,
+                extra_width: 0,
+            };
+            let mut char_target = Transformed::new(target, map, cur_pos);
+            if let Some(bg_color) = self.base.background_color {
+                char_target.clear(bg_color)?;
+            }
+            self.draw_char(seg_pat, &mut char_target)?;
+            cur_pos += Size::new(size.width, 0);
+        }
+        Ok(Point::new(cur_pos.x, pos.y))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.base.draw_whitespace(width, pos, baseline, target)
+    }
+
+    fn measure_string(&self, text: &str, pos: Point, baseline: Baseline) -> TextMetrics {
+        let size = self.base.size;
+        let width = text.chars().filter_map(seg_pattern).count() as u32 * size.width;
+        let top_left = pos - Point::new(0, self.base.baseline_offset(baseline));
+        let bounding_box = Rectangle::new(top_left, Size::new(width, size.height));
+        TextMetrics {
+            bounding_box,
+            next_position: pos + Size::new(width, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.base.line_height()
+    }
+}
@@ -0,0 +1,297 @@
+//! 利用者が持つフレームバッファのスライスに、直接描画する機能です。
+//!
+//! framebufferフィーチャーを有効にすると使えます。スライスへの書き込みは、
+//! DrawTargetを実装した表示器を経由せずに、各ピクセルの色を直接書き込みます。
+//! 塗りつぶしは、行ごとにスライスの範囲をまとめて書き込みます。
+//! 一文字を、ImageRawで読める形式のバイト列に描画する機能もあります。
+use crate::eg;
+use crate::Font7Seg;
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use eg::pixelcolor::raw::RawData;
+use eg::pixelcolor::PixelColor;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::text::renderer::TextRenderer;
+use eg::text::Baseline;
+
+/// 一行stride個の色を並べたスライスを、DrawTargetとして扱うラッパー
+///
+/// 高さは、スライスに入る行数(端数は捨てる)。範囲外の点は描画しない。
+struct SliceTarget<'a, C> {
+    buf: &'a mut [C],
+    stride: usize,
+    size: Size,
+}
+
+impl<'a, C> SliceTarget<'a, C> {
+    fn new(buf: &'a mut [C], stride: usize) -> Self {
+        let height = buf.len().checked_div(stride).unwrap_or(0);
+        let size = Size::new(stride as u32, height as u32);
+        Self { buf, stride, size }
+    }
+}
+
+impl<C> OriginDimensions for SliceTarget<'_, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: PixelColor> DrawTarget for SliceTarget<'_, C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        for Pixel(p, color) in pixels {
+            if area.contains(p) {
+                self.buf[p.y as usize * self.stride + p.x as usize] = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        let left = area.top_left.x as usize;
+        let right = left + area.size.width as usize;
+        for row in area.rows() {
+            let start = row as usize * self.stride;
+            self.buf[start + left..start + right].fill(color);
+        }
+        Ok(())
+    }
+}
+
+/// render_glyph_to_bufferで、一つのピクセルが複数バイトの色を書き込む順序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawByteOrder {
+    /// 上位のバイトから書き込む(ImageRawの初期値と同じ)
+    BigEndian,
+    /// 下位のバイトから書き込む
+    LittleEndian,
+}
+
+/// 一行stride_bytesバイトに詰めたピクセルのバイト列を、DrawTargetとして扱うラッパー
+///
+/// 点は、offsetだけずらしてから書き込む。範囲外の点は描画しない。
+struct PackedTarget<'a, C> {
+    buf: &'a mut [u8],
+    size: Size,
+    offset: Point,
+    stride_bytes: usize,
+    order: RawByteOrder,
+    color: PhantomData<C>,
+}
+
+impl<C> PackedTarget<'_, C>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+{
+    /// 点(x, y)に、色の生データを書き込む。
+    fn set(&mut self, x: usize, y: usize, color: C) {
+        let bits = C::Raw::BITS_PER_PIXEL;
+        let value: u32 = C::Raw::from(color).into_inner().into();
+        let row = &mut self.buf[y * self.stride_bytes..(y + 1) * self.stride_bytes];
+        if bits < 8 {
+            // 1バイトに複数のピクセルを、左のピクセルから上位のビットに詰める。
+            let bit = x * bits;
+            let shift = 8 - bits - bit % 8;
+            let mask = (((1u32 << bits) - 1) << shift) as u8;
+            let byte = &mut row[bit / 8];
+            *byte = (*byte & !mask) | ((value << shift) as u8 & mask);
+        } else {
+            let len = bits / 8;
+            let bytes = &mut row[x * len..(x + 1) * len];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                let index = match self.order {
+                    RawByteOrder::BigEndian => len - 1 - i,
+                    RawByteOrder::LittleEndian => i,
+                };
+                *byte = (value >> (index * 8)) as u8;
+            }
+        }
+    }
+}
+
+impl<C> OriginDimensions for PackedTarget<'_, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C> DrawTarget for PackedTarget<'_, C>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        for Pixel(p, color) in pixels {
+            let p = p - self.offset;
+            if area.contains(p) {
+                self.set(p.x as usize, p.y as usize, color);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C> Font7Seg<C>
+where
+    C: PixelColor,
+    C::Raw: From<C>,
+    <C::Raw as RawData>::Storage: Into<u32>,
+{
+    /// 一文字を、embedded_graphicsのImageRawで読める形式で、bufに描画します。
+    /// * `c`     - 描画する文字
+    /// * `buf`   - 描画先のバイト列
+    /// * `order` - 一つのピクセルが複数バイトの場合の、バイトの順序
+    ///
+    /// 画像の大きさは、measure_stringのbounding_boxの大きさ(通常は一文字分のセル)です。
+    /// 各ピクセルは、色の型の生データ(BinaryColorは1ビット、Rgb565は2バイトなど)で、
+    /// 上の行から順に、左から右へ並べます。1ピクセルが8ビット未満の場合は、1バイトに
+    /// 左のピクセルから上位のビットに詰めます。各行は、バイトの区切りまで0で埋めるので、
+    /// 一行のバイト数は`ceil(幅 × ビット数 / 8)`、全体ではその高さ倍が必要です。
+    /// 描画しないピクセルは、背景色を指定した場合は背景色、指定しない場合は0です。
+    /// 書き込んだバイト列は、`ImageRaw::<C, BO>::new(buf, 幅)`で、各文字の画像として
+    /// 繰り返し描画できます。BOには、orderと同じバイト順の型を指定してください。
+    ///
+    /// <戻り値>
+    /// 画像の大きさを返します。未対応の文字か、bufが短い場合はNoneを返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::{BinaryColor, Rgb565}};
+    /// # use embedded_graphics::image::{Image, ImageRaw};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use embedded_graphics::pixelcolor::raw::LittleEndian;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use font_7seg::{Font7Seg, RawByteOrder};
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut buf = [0; 2 * 20];
+    /// let size = font.render_glyph_to_buffer('7', &mut buf, RawByteOrder::BigEndian).unwrap();
+    /// assert_eq!(size, Size::new(10, 20));
+    /// let sprite: ImageRaw<BinaryColor> = ImageRaw::new(&buf, size.width);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Image::new(&sprite, Point::new(3, 2)).draw(&mut display).unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("7", Point::new(3, 2), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// for p in Rectangle::new(Point::new(3, 2), size).points() {
+    ///     assert_eq!(display.get_pixel(p), Some(expected.get_pixel(p).unwrap_or(BinaryColor::Off)));
+    /// }
+    ///
+    /// // 2バイトの色では、orderのバイト順で書き込む
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb565::RED);
+    /// let mut buf = [0; 10 * 2 * 20];
+    /// font.render_glyph_to_buffer('7', &mut buf, RawByteOrder::LittleEndian).unwrap();
+    /// let sprite: ImageRaw<Rgb565, LittleEndian> = ImageRaw::new(&buf, 10);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// Image::new(&sprite, Point::zero()).draw(&mut display).unwrap();
+    /// let lit = display.affected_area().points().filter(|&p| display.get_pixel(p) == Some(Rgb565::RED));
+    /// assert!(lit.count() > 0);
+    /// ```
+    pub fn render_glyph_to_buffer(
+        &self,
+        c: char,
+        buf: &mut [u8],
+        order: RawByteOrder,
+    ) -> Option<Size> {
+        if !crate::is_supported(c) {
+            return None;
+        }
+        let mut text = [0; 4];
+        let text = c.encode_utf8(&mut text);
+        let bounds = self
+            .drawn_metrics(text, Point::zero(), Baseline::Top)
+            .bounding_box;
+        let stride_bytes = (bounds.size.width as usize * C::Raw::BITS_PER_PIXEL).div_ceil(8);
+        let buf = buf.get_mut(..stride_bytes * bounds.size.height as usize)?;
+        buf.fill(0);
+        let mut target = PackedTarget {
+            buf,
+            size: bounds.size,
+            offset: bounds.top_left,
+            stride_bytes,
+            order,
+            color: PhantomData,
+        };
+        if let Some(bg_color) = self.background_color() {
+            let area = target.bounding_box();
+            match target.fill_solid(&area, bg_color) {
+                Ok(()) => {}
+                Err(never) => match never {},
+            }
+        }
+        match self.draw_string(text, Point::zero(), Baseline::Top, &mut target) {
+            Ok(_) => Some(bounds.size),
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl<C: PixelColor> Font7Seg<C> {
+    /// 文字列を、フレームバッファのスライスbufに、posを左上隅として描画します。
+    ///
+    /// bufは、一行stride個のピクセルを、上の行から順に並べたものです。高さは、bufに入る
+    /// 行数です。はみ出す部分は描画しません。描画結果は、同じ大きさの表示器に
+    /// Baseline::Topでdraw_stringした場合と同じです。
+    ///
+    /// <戻り値>
+    /// 描画後のカーソル位置を返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut buf = [BinaryColor::Off; 64 * 24];
+    /// let next = font.draw_string_to_slice("12.5", Point::new(2, 1), &mut buf, 64);
+    ///
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let expected = Text::with_baseline("12.5", Point::new(2, 1), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(next, expected);
+    /// for y in 0..24 {
+    ///     for x in 0..64 {
+    ///         let p = Point::new(x, y);
+    ///         let expected = display.get_pixel(p).unwrap_or(BinaryColor::Off);
+    ///         assert_eq!(buf[(y * 64 + x) as usize], expected);
+    ///     }
+    /// }
+    /// ```
+    pub fn draw_string_to_slice(
+        &self,
+        text: &str,
+        pos: Point,
+        buf: &mut [C],
+        stride: usize,
+    ) -> Point {
+        let mut target = SliceTarget::new(buf, stride);
+        match self.draw_string(text, pos, Baseline::Top, &mut target) {
+            Ok(next) => next,
+            Err(never) => match never {},
+        }
+    }
+}
@@ -1,19 +1,129 @@
 //! これは、embedded_graphics対応の、7セグメントLED風フォントです。
 //!
-//! 実装する文字は、"0123456789."の11種類です。他の文字を渡すと無視します。
+//! 実装する文字は、"0123456789."の11種類と、16進数の"AbCdEF"、
+//! マイナス記号"-"、コロン":"、度記号"°"です。空白" "は、何も点灯しない一桁分のセルになります。
+//! "1,234,567"のような桁区切りのために、カンマ","も表示できます。本物の7セグメント表示には
+//! カンマが無いので、小数点の丸に、左下へ伸びる三角形の尾を付けた形で近似します。
+//! 電卓風の表示のために、プラス記号"+"と等号"="も表示できます。"+"はセグメントGに、
+//! 同じ形の縦の線を重ねた形、"="はセグメントGとDで、どちらも7セグメント風の近似です。
+//! 入力欄の空欄("__:__"など)のために、下線"_"も表示できます。下線はセグメントDだけを
+//! 点灯した一桁分のセルなので、数字の下端と揃います。
+//!
+//! ```
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+//! # use embedded_graphics::mock_display::MockDisplay;
+//! # use font_7seg::{Font7Seg, Segment};
+//! let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+//! let mut underscore: MockDisplay<BinaryColor> = MockDisplay::new();
+//! Text::with_baseline("_", Point::zero(), font, Baseline::Top)
+//!     .draw(&mut underscore)
+//!     .unwrap();
+//! let mut segment_d: MockDisplay<BinaryColor> = MockDisplay::new();
+//! font.draw_segments(&[Segment::D], Point::zero(), &mut segment_d).unwrap();
+//! underscore.assert_eq(&segment_d);
+//! ```
+//! 16進数の文字は、大文字・小文字のどちらでも同じ字形で表示します。
+//! 全角の数字"０"〜"９"と小数点"．"も、半角の文字と同じ字形で表示します。
+//!
+//! "Err"や"Lo"、"PoE"のような状態表示のために、次の英字も表示できます。
+//! - 大文字・小文字のどちらでも同じ字形 : J, L, n, P, r, t, y
+//! - 大文字のみ : H
+//! - 小文字のみ : o, u
+//!
+//! 他の文字を渡すと無視します。
+//!
+//! 英字を表示したい場合は、14セグメント風のFont14Segも使えます。
+//! 同じフォントで繰り返し描画する場合は、Font7Seg::prepared()で変換した
+//! PreparedFont7Segを使うと、セグメントの形の計算を省けます。
 //!
 //! Font7Seg::new()で、任意の大きさと色を指定することができます。
+//! セグメントの太さやマージンは、with_line_width_rate()などのメソッドを
+//! new()に続けて呼び出すことで変更できます。
 //! embedded_graphics::text::CharacterStyleも実装しますが、有効なのは、
 //! set_text_colorとset_background_colorのみです。
 //! その他は、空の実装を継承しています。
 //!
-//! embedded_graphics::TextRendererの実装中、BaseLine引数に関しては、未実装です。
-//! 何を指定しても、原点は、左上隅となります。
+//! embedded_graphics::TextRendererのBaseline引数は、次のように解釈します。
+//! - Top        : 原点が、数字の上端
+//! - Middle     : 原点が、数字の上下中央
+//! - Bottom     : 原点が、数字の下端
+//! - Alphabetic : 原点が、セグメントDの下端(マージンを除いた、数字の下端の行)
+//!
+//! Alphabeticでは、通常のフォントで描いた単位などのラベルと、ベースラインが揃います。
+//! 原点から数字の上端までの距離は、Font7Seg::baseline_offset()で求められます。
+//! with_origin(Origin::Center)を指定した場合は、Baselineによらず、原点が文字列の
+//! 上下左右の中央になります。
+//!
+//! measure_string()が返すbounding_boxも、同じ解釈で上下の位置を決めます。
+//! next_positionは、Baselineによらず、原点と同じ高さです。
+//!
+//! ```
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+//! # use embedded_graphics::mock_display::MockDisplay;
+//! # use font_7seg::Font7Seg;
+//! let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+//! let draw = |y: i32, baseline: Baseline| {
+//!     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+//!     Text::with_baseline("8", Point::new(0, y), font, baseline)
+//!         .draw(&mut display)
+//!         .unwrap();
+//!     display
+//! };
+//! // Topで描いた場合より、上にずれるピクセル数
+//! for (baseline, shift) in [
+//!     (Baseline::Top, 0),
+//!     (Baseline::Middle, 9),
+//!     (Baseline::Alphabetic, 18),
+//!     (Baseline::Bottom, 19),
+//! ] {
+//!     draw(30, baseline).assert_eq(&draw(30 - shift, Baseline::Top));
+//! }
+//! ```
+//! 各文字の幅は、セルの大きさから決まる整数のピクセル数です。with_scale()で幅に端数が
+//! 出る場合は、端数を文字ごとに溜めて、1ピクセルずつ配分します。draw_string()と
+//! measure_string()は同じ幅で進むので、桁数が多くても、描画後のカーソル位置は
+//! measure_string()のnext_positionと一致します(with_measure_narrow_oneを指定した場合を除く)。
+//!
+//! ```
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+//! # use embedded_graphics::mock_display::MockDisplay;
+//! # use font_7seg::Font7Seg;
+//! for width in 8..64 {
+//!     let font = Font7Seg::new(Size::new(width, width * 2), BinaryColor::On);
+//!     let measured = font.measure_string("0.", Point::zero(), Baseline::Top).next_position;
+//!     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+//!     display.set_allow_out_of_bounds_drawing(true);
+//!     let drawn = Text::with_baseline("0.", Point::zero(), font, Baseline::Top)
+//!         .draw(&mut display)
+//!         .unwrap();
+//!     assert_eq!(measured, drawn);
+//! }
+//!
+//! // マージンだけでセルの幅を超える細いセルや、小数点の幅の比率を変えた場合も同じ
+//! for width in 1..40 {
+//!     for (margin, point_rate) in [(0.0, 0.2), (0.25, 0.2), (0.25, 0.5), (0.1, 0.0)] {
+//!         let font = Font7Seg::new(Size::new(width, 20), BinaryColor::On)
+//!             .with_margins(margin, margin)
+//!             .with_point_width_rate(point_rate);
+//!         let measured = font.measure_string("3.14", Point::zero(), Baseline::Top).next_position;
+//!         let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+//!         display.set_allow_out_of_bounds_drawing(true);
+//!         display.set_allow_overdraw(true);
+//!         let drawn = Text::with_baseline("3.14", Point::zero(), font, Baseline::Top)
+//!             .draw(&mut display)
+//!             .unwrap();
+//!         assert_eq!(measured, drawn);
+//!     }
+//! }
+//! ```
+//!
+//! Text::new()の既定のBaselineはAlphabeticなので、原点を左上隅にする場合は、
+//! Text::with_baseline()でBaseline::Topを指定してください。
 //!
 //! # Examples
 //!
 //! ```
-//! # use embedded_graphics::{prelude::*, text::Text, pixelcolor::Rgb565};
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
 //! # use font_7seg::Font7Seg;
 //! # use embedded_graphics::mock_display::MockDisplay;
 //! # fn try_main() -> Result<(), core::convert::Infallible> {
@@ -21,7 +131,7 @@
 //! # display.set_allow_out_of_bounds_drawing(true);
 //! # display.set_allow_overdraw(true);
 //! let font = Font7Seg::new(Size::new(10,20), Rgb565::RED);
-//! Text::new("0123", Point::new(1,1), font).draw(&mut display)?;
+//! Text::with_baseline("0123", Point::new(1,1), font, Baseline::Top).draw(&mut display)?;
 //! # Ok(())
 //! # }
 //! # fn main() {
@@ -29,42 +139,476 @@
 //! # }
 //! ```
 //!
+//! 全角の数字も、半角の数字と同じように描画します。
+//!
+//! ```
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+//! # use embedded_graphics::mock_display::MockDisplay;
+//! # use font_7seg::Font7Seg;
+//! let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+//! let mut fullwidth: MockDisplay<BinaryColor> = MockDisplay::new();
+//! Text::with_baseline("１２．３", Point::zero(), font, Baseline::Top)
+//!     .draw(&mut fullwidth)
+//!     .unwrap();
+//! let mut ascii: MockDisplay<BinaryColor> = MockDisplay::new();
+//! Text::with_baseline("12.3", Point::zero(), font, Baseline::Top)
+//!     .draw(&mut ascii)
+//!     .unwrap();
+//! fullwidth.assert_eq(&ascii);
+//! ```
+//!
+//! 画面の右端や下端にかかる文字は、画面からはみ出す部分だけを描画しません。
+//! 描画先が範囲外への描画を許さない場合でも、そのまま描画できます。
+//!
+//! ```
+//! # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+//! # use embedded_graphics::mock_display::MockDisplay;
+//! # use font_7seg::Font7Seg;
+//! let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+//! // MockDisplayは、初期状態で範囲外への描画を許さない(描画するとパニックする)
+//! let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+//! Text::with_baseline("8.8", Point::new(50, 52), font, Baseline::Top)
+//!     .draw(&mut display)
+//!     .unwrap();
+//! // 画面の内側には、範囲外を無視する描画先と同じピクセルが残る
+//! let mut lenient: MockDisplay<BinaryColor> = MockDisplay::new();
+//! lenient.set_allow_out_of_bounds_drawing(true);
+//! Text::with_baseline("8.8", Point::new(50, 52), font, Baseline::Top)
+//!     .draw(&mut lenient)
+//!     .unwrap();
+//! display.assert_eq(&lenient);
+//! ```
+//!
 #![no_std]
 use embedded_graphics as eg;
+#[cfg_attr(test, allow(unused_imports))]
 use num_traits::float::FloatCore;
 
-use eg::pixelcolor::PixelColor;
+use eg::pixelcolor::{PixelColor, Rgb888};
 use eg::prelude::*;
-use eg::primitives::{Circle, PrimitiveStyleBuilder, Rectangle, Triangle};
+use eg::primitives::{
+    Circle, ContainsPoint, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, RoundedRectangle,
+    StrokeAlignment, Triangle,
+};
 use eg::text::renderer::{CharacterStyle, TextMetrics, TextRenderer};
 use eg::text::Baseline;
 
+use core::iter::{self, Peekable};
+use core::marker::PhantomData;
+use core::str::Chars;
+
+mod blend;
+mod builder;
+#[cfg(feature = "debug-render")]
+mod debug_render;
+mod font14seg;
+#[cfg(feature = "framebuffer")]
+mod framebuffer;
+mod prepared;
+#[cfg(feature = "serde")]
+mod serde_def;
+mod transform;
+use blend::Blended;
+pub use blend::ReadPixel;
+pub use builder::Font7SegBuilder;
+pub use font14seg::Font14Seg;
+#[cfg(feature = "framebuffer")]
+pub use framebuffer::RawByteOrder;
+pub use prepared::PreparedFont7Seg;
+use transform::{CellMap, Rotated, Transformed};
+
 /// 7セグメントLED風フォント
+///
+/// serdeフィーチャーを有効にすると、設定をシリアライズできます。デシリアライズは、
+/// Rgb888と相互に変換できる色で使えます。アンチエイリアスや明るさ、不透明度の設定も
+/// そのまま復元され、範囲外の比率やNaNは、with_*メソッドと同じく丸め込みます。
+///
+/// fixed-pointフィーチャーを有効にすると、セルの大きさからマージンやセグメントの太さを
+/// 求める計算を、整数だけで行います。比率の設定は、f32のまま指定できます。
+/// 整数の計算は、f32の掛け算の丸めも再現するので、描画結果は、通常の計算と同じです。
+/// 次の例は、どちらの計算でも成り立ちます。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+/// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+/// # use font_7seg::Font7Seg;
+/// for (size, area, next_x) in [
+///     (Size::new(10, 20), Rectangle::new(Point::new(1, 1), Size::new(22, 18)), 24),
+///     (Size::new(13, 27), Rectangle::new(Point::new(1, 2), Size::new(29, 23)), 31),
+///     (Size::new(24, 50), Rectangle::new(Point::new(2, 3), Size::new(52, 44)), 56),
+/// ] {
+///     let font = Font7Seg::new(size, BinaryColor::On);
+///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+///     let next = Text::with_baseline("8.8", Point::zero(), font, Baseline::Top)
+///         .draw(&mut display)
+///         .unwrap();
+///     assert_eq!(display.affected_area(), area);
+///     assert_eq!(next.x, next_x);
+/// }
+/// ```
+///
+/// 全ての数字と記号の字形は、次のとおりです。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::{BinaryColor, Rgb565}};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # use font_7seg::Font7Seg;
+/// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+/// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// Text::with_baseline("01234", Point::zero(), font, Baseline::Top)
+///     .draw(&mut display)
+///     .unwrap();
+/// Text::with_baseline("56789.-", Point::new(0, 20), font, Baseline::Top)
+///     .draw(&mut display)
+///     .unwrap();
+/// display.assert_pattern(&[
+///     "                                                             ",
+///     "   ####                ####      ####                        ",
+///     " #  ##  #         #     ##  #     ##  #  #      #            ",
+///     " ##    ##        ##        ##        ##  ##    ##            ",
+///     " ##    ##        ##        ##        ##  ##    ##            ",
+///     " ##    ##        ##        ##        ##  ##    ##            ",
+///     " ##    ##        ##        ##        ##  ##    ##            ",
+///     " ##    ##        ##        ##        ##  ##    ##            ",
+///     " #      #         #         #         #  #      #            ",
+///     "                       ####      ####      ####              ",
+///     "                        ##        ##        ##               ",
+///     " #      #         #  #                #         #            ",
+///     " ##    ##        ##  ##              ##        ##            ",
+///     " ##    ##        ##  ##              ##        ##            ",
+///     " ##    ##        ##  ##              ##        ##            ",
+///     " ##    ##        ##  ##              ##        ##            ",
+///     " ##    ##        ##  ##              ##        ##            ",
+///     " #  ##  #         #  #  ##        ##  #         #            ",
+///     "   ####                ####      ####                        ",
+///     "                                                             ",
+///     "                                                             ",
+///     "   ####      ####      ####      ####      ####              ",
+///     " #  ##     #  ##     #  ##  #  #  ##  #  #  ##  #            ",
+///     " ##        ##        ##    ##  ##    ##  ##    ##            ",
+///     " ##        ##        ##    ##  ##    ##  ##    ##            ",
+///     " ##        ##        ##    ##  ##    ##  ##    ##            ",
+///     " ##        ##        ##    ##  ##    ##  ##    ##            ",
+///     " ##        ##        ##    ##  ##    ##  ##    ##            ",
+///     " #         #         #      #  #      #  #      #            ",
+///     "   ####      ####                ####      ####          ####",
+///     "    ##        ##                  ##        ##            ## ",
+///     "        #  #      #         #  #      #         #            ",
+///     "       ##  ##    ##        ##  ##    ##        ##            ",
+///     "       ##  ##    ##        ##  ##    ##        ##            ",
+///     "       ##  ##    ##        ##  ##    ##        ##            ",
+///     "       ##  ##    ##        ##  ##    ##        ##            ",
+///     "       ##  ##    ##        ##  ##    ##        ##            ",
+///     "    ##  #  #  ##  #         #  #  ##  #     ##  #   ##       ",
+///     "   ####      ####                ####      ####     ##       ",
+/// ]);
+///
+/// // 消灯したセグメントの色を指定すると、点灯するセグメントと消灯するセグメントを塗り分ける
+/// let font = Font7Seg::new(Size::new(10, 20), Rgb565::RED).with_off_color(Rgb565::BLUE);
+/// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+/// display.set_allow_overdraw(true);
+/// Text::with_baseline("01234", Point::zero(), font, Baseline::Top)
+///     .draw(&mut display)
+///     .unwrap();
+/// Text::with_baseline("56789.-", Point::new(0, 20), font, Baseline::Top)
+///     .draw(&mut display)
+///     .unwrap();
+/// display.assert_pattern(&[
+///     "                                                               ",
+///     "   RRRR      BBBB      RRRR      RRRR      BBBB                ",
+///     " R  RR  R  B  BB  R  B  RR  R  B  RR  R  R  BB  R              ",
+///     " RR    RR  BB    RR  BB    RR  BB    RR  RR    RR              ",
+///     " RR    RR  BB    RR  BB    RR  BB    RR  RR    RR              ",
+///     " RR    RR  BB    RR  BB    RR  BB    RR  RR    RR              ",
+///     " RR    RR  BB    RR  BB    RR  BB    RR  RR    RR              ",
+///     " RR    RR  BB    RR  BB    RR  BB    RR  RR    RR              ",
+///     " R      R  B      R  B      R  B      R  R      R              ",
+///     "   BBBB      BBBB      RRRR      RRRR      RRRR                ",
+///     "    BB        BB        RR        RR        RR                 ",
+///     " R      R  B      R  R      B  B      R  B      R              ",
+///     " RR    RR  BB    RR  RR    BB  BB    RR  BB    RR              ",
+///     " RR    RR  BB    RR  RR    BB  BB    RR  BB    RR              ",
+///     " RR    RR  BB    RR  RR    BB  BB    RR  BB    RR              ",
+///     " RR    RR  BB    RR  RR    BB  BB    RR  BB    RR              ",
+///     " RR    RR  BB    RR  RR    BB  BB    RR  BB    RR              ",
+///     " R  RR  R  B  BB  R  R  RR  B  B  RR  R  B  BB  R              ",
+///     "   RRRR      BBBB      RRRR      RRRR      BBBB                ",
+///     "                                                               ",
+///     "                                                               ",
+///     "   RRRR      RRRR      RRRR      RRRR      RRRR          BBBB  ",
+///     " R  RR  B  R  RR  B  R  RR  R  R  RR  R  R  RR  R      B  BB  B",
+///     " RR    BB  RR    BB  RR    RR  RR    RR  RR    RR      BB    BB",
+///     " RR    BB  RR    BB  RR    RR  RR    RR  RR    RR      BB    BB",
+///     " RR    BB  RR    BB  RR    RR  RR    RR  RR    RR      BB    BB",
+///     " RR    BB  RR    BB  RR    RR  RR    RR  RR    RR      BB    BB",
+///     " RR    BB  RR    BB  RR    RR  RR    RR  RR    RR      BB    BB",
+///     " R      B  R      B  R      R  R      R  R      R      B      B",
+///     "   RRRR      RRRR      BBBB      RRRR      RRRR          RRRR  ",
+///     "    RR        RR        BB        RR        RR            RR   ",
+///     " B      R  R      R  B      R  R      R  B      R      B      B",
+///     " BB    RR  RR    RR  BB    RR  RR    RR  BB    RR      BB    BB",
+///     " BB    RR  RR    RR  BB    RR  RR    RR  BB    RR      BB    BB",
+///     " BB    RR  RR    RR  BB    RR  RR    RR  BB    RR      BB    BB",
+///     " BB    RR  RR    RR  BB    RR  RR    RR  BB    RR      BB    BB",
+///     " BB    RR  RR    RR  BB    RR  RR    RR  BB    RR      BB    BB",
+///     " B  RR  R  R  RR  R  B  BB  R  R  RR  R  B  RR  R   RR B  BB  B",
+///     "   RRRR      RRRR      BBBB      RRRR      RRRR     RR   BBBB  ",
+/// ]);
+/// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "serde_def::Font7SegDef<C>",
+        into = "serde_def::Font7SegDef<C>",
+        bound(
+            serialize = "C: PixelColor + serde::Serialize",
+            deserialize = "C: PixelColor + From<Rgb888> + Into<Rgb888> + serde::Deserialize<'de>"
+        )
+    )
+)]
 pub struct Font7Seg<C> {
     size: Size,
     text_color: C,
     background_color: Option<C>,
+    /// 背景のグラデーションの、上端と下端の色
+    background_gradient: Option<(C, C)>,
+    off_color: Option<C>,
+    separator_color: Option<C>,
+    point_as_background: bool,
+    highlight: Option<C>,
+    scanlines: Option<C>,
+    debug_cells: Option<C>,
+    blank_leading_zeros: bool,
+    attached_point: bool,
+    antialiasing: bool,
+    mix: Option<MixFn<C>>,
+    /// 明るさ(0〜255)と、背景色がない場合に混ぜる黒
+    brightness: Option<(u8, C)>,
+    /// draw_string_blendedで、描画先の色に重ねる割合(0〜255)
+    opacity: u8,
+    /// with_scaleで切り捨てた、数字の幅の端数。小数部16ビットの固定小数点(0〜0xffff)
+    width_fraction: u32,
+    /// with_scaleで四捨五入した、数字の高さの誤差(丸める前の高さ - size.height)。
+    /// 小数部16ビットの固定小数点(-0x8000〜0x8000)
+    height_fraction: i32,
+    char_spacing: u32,
+    line_spacing: u32,
+    /// line_height()の値。Noneの場合は、数字の高さと行の間隔から求める
+    line_height: Option<u32>,
+    slant: f32,
+    segment_tilt: f32,
+    mirror_x: bool,
+    rotation: Rotation,
+    origin: Origin,
+    proportional: bool,
+    measure_narrow_one: bool,
+    segment_shape: SegmentShape,
+    corner_radius: u32,
+    segment_style: SegmentStyle,
+    /// セグメントを点の列で描く場合の、点の直径と間隔
+    dot_segments: Option<(u32, u32)>,
+    point_position: PointPosition,
+    point_shape: PointShape,
+    segment_gap: u32,
+    seg_pats: [u8; 16],
     line_width_rate: f32,
     top_margin_rate: f32,
     left_margin_rate: f32,
     point_width_rate: f32,
+    sign_width_rate: f32,
 }
 
 impl<C: PixelColor> Font7Seg<C> {
     /// フォントオブジェクトを生成します。
     /// * `size`       - 表示する数字のサイズ(ピクセル単位)
     /// * `text_color` - 表示する数字の色
-    pub fn new(size: Size, text_color: C) -> Self {
+    ///
+    /// const fnなので、staticなフォントの初期化にも使えます。
+    /// その場合、色もconstな式(Rgb565::REDやRgb565::new()など)で指定する必要があります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::Rgb565};
+    /// # use font_7seg::Font7Seg;
+    /// static FONT: Font7Seg<Rgb565> = Font7Seg::new(Size::new(10, 20), Rgb565::RED);
+    /// ```
+    ///
+    /// 極端に細長いサイズでも、セグメントの形が崩れずに描画されます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(6, 60), BinaryColor::On);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// let area = display.affected_area();
+    /// assert!(!area.is_zero_sized());
+    /// assert!(area.size.width <= 6 && area.size.height <= 60);
+    /// ```
+    ///
+    /// 幅や高さが奇数でも、数字はセルの中央に、左右対称に描画されます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// for (width, height) in [(11, 21), (13, 27), (15, 31)] {
+    ///     let font = Font7Seg::new(Size::new(width, height), BinaryColor::On);
+    ///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///     Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     // セルの縦の中心線で折り返しても、同じ形になる
+    ///     let mut mirrored: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///     for p in display.affected_area().points() {
+    ///         let mirror = Point::new(width as i32 - 1 - p.x, p.y);
+    ///         mirrored.set_pixel(mirror, display.get_pixel(p));
+    ///     }
+    ///     display.assert_eq(&mirrored);
+    ///     // 上下の余白も等しい
+    ///     let area = display.affected_area();
+    ///     let bottom = area.bottom_right().unwrap();
+    ///     assert_eq!(area.top_left.x, width as i32 - 1 - bottom.x);
+    ///     assert_eq!(area.top_left.y, height as i32 - 1 - bottom.y);
+    /// }
+    /// ```
+    pub const fn new(size: Size, text_color: C) -> Self {
         Self {
             size,
             text_color,
             background_color: None,
+            background_gradient: None,
+            off_color: None,
+            separator_color: None,
+            point_as_background: false,
+            highlight: None,
+            scanlines: None,
+            debug_cells: None,
+            blank_leading_zeros: false,
+            attached_point: false,
+            antialiasing: false,
+            mix: None,
+            brightness: None,
+            opacity: 255,
+            width_fraction: 0,
+            height_fraction: 0,
+            char_spacing: 0,
+            line_spacing: 0,
+            line_height: None,
+            slant: 0.0,
+            segment_tilt: 0.0,
+            mirror_x: false,
+            rotation: Rotation::Deg0,
+            origin: Origin::TopLeft,
+            proportional: false,
+            measure_narrow_one: false,
+            segment_shape: SegmentShape::Pointed,
+            corner_radius: 0,
+            segment_style: SegmentStyle::Fill,
+            dot_segments: None,
+            point_position: PointPosition::Bottom,
+            point_shape: PointShape::Circle,
+            segment_gap: 0,
+            seg_pats: SEG_PATS,
             line_width_rate: 0.2,
             top_margin_rate: 0.05,
             left_margin_rate: 0.05,
             point_width_rate: 0.2,
+            sign_width_rate: 0.6,
+        }
+    }
+
+    /// try_newで受け付ける、数字の最小のサイズ
+    pub const MIN_SIZE: Size = Size::new(4, 7);
+
+    /// フォントオブジェクトを生成します。sizeが小さすぎる場合は、エラーを返します。
+    ///
+    /// 幅か高さがMIN_SIZEより小さいと、セグメントが潰れて、数字の形になりません。
+    /// その場合は、Font7SegError::InvalidSizeを返します。その他は、newと同じです。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::{Font7Seg, Font7SegError};
+    /// assert!(Font7Seg::try_new(Size::new(10, 20), BinaryColor::On).is_ok());
+    /// assert_eq!(
+    ///     Font7Seg::try_new(Size::new(0, 20), BinaryColor::On).err(),
+    ///     Some(Font7SegError::InvalidSize(Size::new(0, 20)))
+    /// );
+    /// ```
+    pub fn try_new(size: Size, text_color: C) -> Result<Self, Font7SegError> {
+        if size.width < Self::MIN_SIZE.width || size.height < Self::MIN_SIZE.height {
+            return Err(Font7SegError::InvalidSize(size));
+        }
+        Ok(Self::new(size, text_color))
+    }
+
+    /// 設定を確かめてから生成する、Font7SegBuilderを返します。
+    ///
+    /// セグメントの太さやマージンの組み合わせが、セルに収まるかを確かめたい場合に
+    /// 使います。確かめる必要が無ければ、newで十分です。
+    pub const fn builder(size: Size, text_color: C) -> Font7SegBuilder<C> {
+        Font7SegBuilder::new(size, text_color)
+    }
+
+    /// 数字digits桁がboundsに収まる、最も大きなフォントを生成します。
+    /// * `bounds` - 数字を並べる範囲
+    /// * `digits` - 並べる数字の桁数。0の場合は1として扱います。
+    /// * `ratio`  - 数字の幅と高さの比(幅 / 高さ)。0以下かNaNの場合は、0.5として扱います。
+    /// * `color`  - 表示する数字の色
+    ///
+    /// 数字の幅がboundsの幅のdigits分の1以下、高さがboundsの高さ以下になる範囲で、
+    /// 幅と高さの比がratioになる最大のサイズを、ピクセル単位に切り捨てて求めます。
+    /// 文字間の間隔は0、その他の設定は、newの初期値です。小数点とコロンの幅は含みません。
+    /// 小数点も収めたい場合は、with_attached_pointで数字に重ねるか、digitsを一桁多くして
+    /// ください。生成後にwith_char_spacingなどで幅を広げると、boundsに収まらなくなります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use font_7seg::Font7Seg;
+    /// let bounds = Rectangle::new(Point::new(5, 3), Size::new(100, 40));
+    /// let font = Font7Seg::fit_to(bounds, 4, 0.5, BinaryColor::On);
+    /// assert_eq!(font.character_size(), Size::new(20, 40));
+    ///
+    /// for (size, digits, ratio) in [((100, 40), 4, 0.5), ((64, 200), 3, 0.6), ((37, 13), 5, 0.45)] {
+    ///     let bounds = Rectangle::new(Point::new(5, 3), Size::new(size.0, size.1));
+    ///     let font = Font7Seg::fit_to(bounds, digits, ratio, BinaryColor::On);
+    ///     let text = "8".repeat(digits as usize);
+    ///     let measured = font.measure_string(&text, bounds.top_left, Baseline::Top).bounding_box;
+    ///     assert_eq!(bounds.intersection(&measured), measured);
+    /// }
+    /// ```
+    pub fn fit_to(bounds: Rectangle, digits: u32, ratio: f32, color: C) -> Self {
+        let ratio = if ratio > 0.0 { ratio } else { 0.5 };
+        let max_width = bounds.size.width / digits.max(1);
+        let height = ((max_width as f32 / ratio) as u32).min(bounds.size.height);
+        // 丸め誤差で、幅がはみ出さないようにする。
+        let width = ((height as f32 * ratio) as u32).min(max_width);
+        Self::new(Size::new(width, height), color)
+    }
+
+    /// セグメントの形に関わる設定が、セルに収まるかを確かめる。Font7SegBuilder::build用。
+    pub(crate) fn validate(&self) -> Result<(), Font7SegError> {
+        if self.size.width < Self::MIN_SIZE.width || self.size.height < Self::MIN_SIZE.height {
+            return Err(Font7SegError::InvalidSize(self.size));
         }
+        let Size { width, height } = self.inner_area(self.size).size;
+        let line_width = scale_ceil(width, self.line_width_rate);
+        // 左右の縦のセグメント(BとF)、上中下の横のセグメント(A、G、D)が重ならないこと
+        let overlapped = line_width * 2 > width || line_width * 3 > height;
+        // 隙間の分だけ短くしても、縦と横のセグメントの長さが残ること
+        let gap = self.segment_gap;
+        let vert_length = (height.div_ceil(2) * 4 / 5) as i64 - gap as i64 * 2;
+        let hori_cut = (line_width * 3).div_ceil(5) + gap;
+        let vanished = line_width == 0 || vert_length <= 0 || hori_cut * 2 > width;
+        if overlapped || vanished {
+            return Err(Font7SegError::InvalidGeometry);
+        }
+        Ok(())
     }
 
     /// 現在の表示する数字のサイズを返します。
@@ -72,373 +616,4609 @@ impl<C: PixelColor> Font7Seg<C> {
         self.size
     }
 
-    fn draw_segment_vert<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        // 長さを少し短くする。
-        let Size { width, height } = area.bounding_box().size;
-        let height_diff = height as f32 * 0.1;
-        let area_top = ((height_diff + width as f32) / 2.0).floor();
-        let area_top_left = Point::new(0, area_top as i32);
-        let area_height = (height as f32 - height_diff * 2.0).floor();
-        let area_size = Size::new(width, area_height as u32);
-        let mut area = area.cropped(&Rectangle::new(area_top_left, area_size));
-
-        // 描画
-        let Size { width, height } = area.bounding_box().size;
-        let w_center: f32 = width as f32 / 2.0;
-        let v_base_top: f32 = width as f32 * 1.2 / 2.0;
-        let v_base_bottom: f32 = height as f32 - v_base_top;
-        let w_center: i32 = w_center as i32;
-        let v_base_top: i32 = v_base_top as i32;
-        let v_base_bottom: i32 = v_base_bottom as i32;
-        let points = [
-            Point::new(w_center, 0),
-            Point::new(width as i32, v_base_top),
-            Point::new(width as i32, v_base_bottom),
-            Point::new(w_center, height as i32),
-            Point::new(0, v_base_bottom),
-            Point::new(0, v_base_top),
-        ];
-        self.draw_polygon(&points, &mut area)
+    /// 数字のサイズを、scale倍にします。
+    ///
+    /// 高さは四捨五入、幅は切り捨てた整数のピクセル数のセルに描画します。切り捨てた
+    /// 幅の端数は、数字など通常の幅の文字を並べるごとに溜めていき、溜めた端数を
+    /// 四捨五入した値が1増えるたびに、次の文字を1ピクセル右へずらします。数字だけを
+    /// 並べた場合、n文字目の左端は、拡大後の幅 × (n - 1)を四捨五入した位置になるので、
+    /// 桁数が多くても、セルごとの切り捨てで左へずれていくことはありません。
+    /// measure_stringとmeasure_charsも、同じように端数を配分した幅を返します。
+    /// 小数点やコロンなど、幅の狭い文字の幅は、拡大後のセルから求めた整数のままです。
+    ///
+    /// 繰り返し指定した場合は、それまでに切り捨てた幅の端数と、四捨五入した高さの誤差も
+    /// 含めた、丸める前の大きさに掛けます。with_scale(a).with_scale(b)は、f32の計算の
+    /// 誤差を除いて、with_scale(a * b)と同じ大きさになります。
+    /// scaleが負かNaNの場合は、0.0とみなします。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_scale(1.25);
+    /// assert_eq!(font.character_size(), Size::new(12, 25));
+    ///
+    /// // 幅12.5の数字を、0、12.5、25、37.5を四捨五入した位置に並べる
+    /// let lefts = font.measure_chars("0123", Point::zero()).map(|(_, cell)| cell.top_left.x);
+    /// assert!(lefts.eq([0, 13, 25, 38]));
+    ///
+    /// // 20桁でも、12.5 × 20 = 250ピクセルだけ進む
+    /// let metrics = font.measure_string("01234567890123456789", Point::zero(), Baseline::Top);
+    /// assert_eq!(metrics.next_position, Point::new(250, 0));
+    ///
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = Text::with_baseline("0123", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(next, Point::new(50, 0));
+    ///
+    /// // 続けて指定しても、丸める前の13.7 × 27.4を2倍にする
+    /// let base = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let chained = base.with_scale(1.37).with_scale(2.0);
+    /// assert_eq!(chained.character_size(), Size::new(27, 55));
+    /// assert_eq!(chained.character_size(), base.with_scale(2.74).character_size());
+    /// ```
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        let scale = if scale > 0.0 { scale } else { 0.0 };
+        let width = (self.size.width as f32 + self.width_fraction as f32 / 65536.0) * scale;
+        let height = (self.size.height as f32 + self.height_fraction as f32 / 65536.0) * scale;
+        self.size = Size::new(width as u32, height.round() as u32);
+        self.width_fraction = rate_to_fixed(width.fract()).min(0xffff);
+        let error = (height - height.round()) * 65536.0;
+        self.height_fraction = (error.round() as i32).clamp(-0x8000, 0x8000);
+        self
     }
 
-    fn draw_segment_hori<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        // 両端を幅の半分だけ削る
-        let Size { width, height } = area.bounding_box().size;
-        let half_width: i32 = (height as f32 * 1.2 / 2.0).ceil() as i32;
-        let new_top_left = Point::new(half_width, 0);
-        let new_size = Size::new(width - (half_width * 2) as u32, height);
-        let mut area = area.cropped(&Rectangle::new(new_top_left, new_size));
+    /// セグメントの太さを、数字の幅に対する比率で指定します。(初期値 0.2)
+    ///
+    /// 0.0〜0.5の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    pub fn with_line_width_rate(mut self, rate: f32) -> Self {
+        self.set_line_width_rate(rate);
+        self
+    }
 
-        //セグメントの描画
-        let Size { width, height } = area.bounding_box().size;
-        let v_center: f32 = height as f32 / 2.0;
-        let h_base_left: f32 = height as f32 * 1.2 / 2.0;
-        let h_base_right: f32 = width as f32 - h_base_left;
-        let v_center: i32 = v_center as i32;
-        let h_base_left: i32 = h_base_left as i32;
-        let h_base_right: i32 = h_base_right as i32;
-        let points = [
-            Point::new(0, v_center),
-            Point::new(h_base_left, 0),
-            Point::new(h_base_right, 0),
-            Point::new(width as i32, v_center),
-            Point::new(h_base_right, height as i32),
-            Point::new(h_base_left, height as i32),
-        ];
-        self.draw_polygon(&points, &mut area)
+    /// 上下と左右のマージンを、数字の高さ・幅に対する比率で指定します。(初期値 共に0.05)
+    /// * `top`  - 上下のマージン(高さに対する比率)
+    /// * `left` - 左右のマージン(幅に対する比率)
+    ///
+    /// 0.0〜0.25の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+    /// # use font_7seg::Font7Seg;
+    /// // 上下は高さ40の0.2倍、左右は幅20の0.1倍だけ、内側に描画する
+    /// let font = Font7Seg::new(Size::new(20, 40), BinaryColor::On).with_margins(0.2, 0.1);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     display.affected_area(),
+    ///     Rectangle::new(Point::new(2, 8), Size::new(16, 24))
+    /// );
+    /// ```
+    pub fn with_margins(mut self, top: f32, left: f32) -> Self {
+        self.set_top_margin_rate(top);
+        self.set_left_margin_rate(left);
+        self
     }
 
-    fn draw_polygon<D>(&self, points: &[Point; 6], area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let style = PrimitiveStyleBuilder::new()
-            .fill_color(self.text_color)
-            .build();
-        Triangle::new(points[0], points[1], points[5])
-            .into_styled(style)
-            .draw(area)?;
-        Triangle::new(points[2], points[3], points[4])
-            .into_styled(style)
-            .draw(area)?;
-        Rectangle::with_corners(points[5], points[2])
-            .into_styled(style)
-            .draw(area)?;
-        Ok(())
+    /// 小数点の幅を、数字の幅に対する比率で指定します。(初期値 0.2)
+    ///
+    /// 0.0〜0.5の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    pub fn with_point_width_rate(mut self, rate: f32) -> Self {
+        self.set_point_width_rate(rate);
+        self
     }
 
-    fn draw_seg_a<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, .. } = area.bounding_box().size;
-        let seg_height: u32 = (width as f32 * self.line_width_rate).ceil() as u32;
-        let seg_top_left = Point::new(0, 0);
-        let seg_size = Size::new(width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_hori(&mut seg_target)?;
-        Ok(())
+    /// draw_signedの符号のセルの幅を、数字の幅に対する比率で指定します。(初期値 0.6)
+    ///
+    /// 0.0〜1.0の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    pub fn with_sign_width_rate(mut self, rate: f32) -> Self {
+        self.sign_width_rate = clamp_rate(rate, 1.0);
+        self
     }
 
-    fn draw_seg_b<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_width: f32 = width as f32 * self.line_width_rate;
-        let seg_width: u32 = seg_width.ceil() as u32;
-        let seg_height: u32 = (height as f32 / 2.0).ceil() as u32;
-        let seg_top_left = Point::new((width - seg_width) as i32, 0);
-        let seg_size = Size::new(seg_width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_vert(&mut seg_target)?;
-        Ok(())
+    /// 文字色を返します。
+    pub fn text_color(&self) -> C {
+        self.text_color
     }
 
-    fn draw_seg_c<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_width: f32 = width as f32 * self.line_width_rate;
-        let seg_width_half: f32 = seg_width / 2.0;
-        let seg_width: u32 = seg_width.ceil() as u32;
-        let seg_height: u32 = (height as f32 / 2.0).ceil() as u32;
-        let seg_top: i32 = (height as f32 / 2.0 - seg_width_half).ceil() as i32;
-        let seg_left: i32 = (width - seg_width) as i32;
-        let seg_top_left = Point::new(seg_left, seg_top);
-        let seg_size = Size::new(seg_width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_vert(&mut seg_target)?;
-        Ok(())
+    /// 背景色を返します。背景を塗らない設定の場合はNoneです。
+    ///
+    /// 文字色と背景色の変更には、CharacterStyleのset_text_color、
+    /// set_background_colorを使います。
+    pub fn background_color(&self) -> Option<C> {
+        self.background_color
     }
 
-    fn draw_seg_d<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_height: f32 = width as f32 * self.line_width_rate;
-        let seg_top: i32 = (height as f32 - seg_height).floor() as i32;
-        let seg_height: u32 = seg_height.ceil() as u32;
-        let seg_top_left = Point::new(0, seg_top);
-        let seg_size = Size::new(width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_hori(&mut seg_target)?;
-        Ok(())
+    /// セグメントの太さの比率を返します。
+    pub fn line_width_rate(&self) -> f32 {
+        self.line_width_rate
     }
 
-    fn draw_seg_e<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_width: f32 = width as f32 * self.line_width_rate;
-        let seg_width_half: f32 = seg_width / 2.0;
-        let seg_width: u32 = seg_width.ceil() as u32;
-        let seg_height: u32 = (height as f32 / 2.0).ceil() as u32;
-        let seg_top: i32 = (height as f32 / 2.0 - seg_width_half).ceil() as i32;
-        let seg_top_left = Point::new(0, seg_top);
-        let seg_size = Size::new(seg_width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_vert(&mut seg_target)?;
-        Ok(())
+    /// セグメントの太さの比率を変更します。値の扱いは、with_line_width_rateと同じです。
+    pub fn set_line_width_rate(&mut self, rate: f32) {
+        self.line_width_rate = clamp_rate(rate, 0.5);
     }
 
-    fn draw_seg_f<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_width: f32 = width as f32 * self.line_width_rate;
-        let seg_width: u32 = seg_width.ceil() as u32;
-        let seg_height: u32 = (height as f32 / 2.0).ceil() as u32;
-        let seg_top_left = Point::new(0, 0);
-        let seg_size = Size::new(seg_width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_vert(&mut seg_target)?;
-        Ok(())
+    /// 上下のマージンの比率を返します。
+    pub fn top_margin_rate(&self) -> f32 {
+        self.top_margin_rate
     }
 
-    fn draw_seg_g<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let Size { width, height } = area.bounding_box().size;
-        let seg_height: f32 = width as f32 * self.line_width_rate;
-        let seg_height_half: f32 = seg_height / 2.0;
-        let seg_top = (height as f32 / 2.0 - seg_height_half).floor();
-        let seg_top = seg_top as i32;
-        let seg_height: u32 = seg_height.ceil() as u32;
-        let seg_top_left = Point::new(0, seg_top);
-        let seg_size = Size::new(width, seg_height);
-        let mut seg_target = area.cropped(&Rectangle::new(seg_top_left, seg_size));
-        self.draw_segment_hori(&mut seg_target)?;
-        Ok(())
+    /// 上下のマージンの比率を変更します。値の扱いは、with_marginsと同じです。
+    pub fn set_top_margin_rate(&mut self, rate: f32) {
+        self.top_margin_rate = clamp_rate(rate, 0.25);
     }
 
-    fn draw_seg_point<D>(&self, area: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        // 幅を狭くする
-        let Size { width, height } = area.bounding_box().size;
-        let n_width: f32 = (width as f32 * self.point_width_rate).ceil();
-        let n_size = Size::new(n_width as u32, height);
-        let mut area = area.cropped(&Rectangle::new(Point::new(0, 0), n_size));
+    /// 左右のマージンの比率を返します。
+    pub fn left_margin_rate(&self) -> f32 {
+        self.left_margin_rate
+    }
 
-        // 丸を描画
-        let Size { width, height } = area.bounding_box().size;
-        let radius = width as f32 / 2.0;
-        let center_y = height as f32 - radius * 2.0;
-        let center = Point::new(radius.floor() as i32, center_y.floor() as i32);
-        let style = PrimitiveStyleBuilder::new()
-            .fill_color(self.text_color)
-            .build();
-        Circle::with_center(center, (radius * 2.0).floor() as u32)
-            .into_styled(style)
-            .draw(&mut area)?;
-        Ok(())
+    /// 左右のマージンの比率を変更します。値の扱いは、with_marginsと同じです。
+    pub fn set_left_margin_rate(&mut self, rate: f32) {
+        self.left_margin_rate = clamp_rate(rate, 0.25);
     }
 
-    /// 数字を一文字描画する。
-    /// <引数>
-    /// * num: 描画する数字一桁。10以上の数値の場合、一桁目のみ有効。
-    /// * point: trueの場合、小数点を描画する。numは無視される。
-    /// * area: 描画対象のDrawTargetの可変参照
-    /// <戻り値>
-    /// 正常の場合、描画した幅を返す。DrawTarget.draw()のエラーの可能性あり。
+    /// 小数点の幅の比率を返します。
+    pub fn point_width_rate(&self) -> f32 {
+        self.point_width_rate
+    }
+
+    /// 小数点の幅の比率を変更します。値の扱いは、with_point_width_rateと同じです。
+    pub fn set_point_width_rate(&mut self, rate: f32) {
+        self.point_width_rate = clamp_rate(rate, 0.5);
+    }
+
+    /// 消灯しているセグメントを、薄く表示する色を指定します。(初期値 なし)
     ///
-    fn draw_number<D>(&self, num: u32, point: bool, area: &mut D) -> Result<u32, D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        // areaから、マージンを削除して領域を再定義
-        let Size { width, height } = area.bounding_box().size;
-        let all_area_width = width;
-        let top_margin: f32 = height as f32 * self.top_margin_rate;
-        let left_margin: f32 = width as f32 * self.left_margin_rate;
-        let top_left = Point::new(top_margin.ceil() as i32, left_margin.ceil() as i32);
-        let size = Size::new(
-            width - left_margin.ceil() as u32 * 2,
-            height - top_margin.ceil() as u32 * 2,
-        );
-        let mut area = area.cropped(&Rectangle::new(top_left, size));
-        //　描画
-        const SEG_PATS: [u8; 10] = [
-            0b0011_1111,
-            0b0000_0110,
-            0b0101_1011,
-            0b0100_1111,
-            0b0110_0110,
-            0b0110_1101,
-            0b0111_1101,
-            0b0010_0111,
-            0b0111_1111,
-            0b0110_1111,
-        ];
-        if point {
-            self.draw_seg_point(&mut area)?;
-        } else {
-            let seg_pat = SEG_PATS[(num % 10) as usize];
-            if seg_pat & 0b0000_0001 != 0 {
-                self.draw_seg_a(&mut area)?;
-            }
-            if seg_pat & 0b0000_0010 != 0 {
-                self.draw_seg_b(&mut area)?;
-            }
-            if seg_pat & 0b0000_0100 != 0 {
-                self.draw_seg_c(&mut area)?;
-            }
-            if seg_pat & 0b0000_1000 != 0 {
-                self.draw_seg_d(&mut area)?;
-            }
-            if seg_pat & 0b0001_0000 != 0 {
-                self.draw_seg_e(&mut area)?;
-            }
-            if seg_pat & 0b0010_0000 != 0 {
-                self.draw_seg_f(&mut area)?;
-            }
-            if seg_pat & 0b0100_0000 != 0 {
-                self.draw_seg_g(&mut area)?;
-            }
-        }
+    /// 指定すると、全セグメントをこの色で描画してから、点灯するセグメントを
+    /// 文字色で重ねて描画します。実物の液晶表示のような見た目になります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, Segment};
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb565::RED).with_off_color(Rgb565::BLUE);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// Text::with_baseline("1", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 各セグメントの中央の点の色
+    /// let outlines = font.segment_outlines();
+    /// let color = |seg: Segment| {
+    ///     let points = outlines[seg as usize];
+    ///     display.get_pixel((points[0] + points[3]) / 2)
+    /// };
+    /// for seg in [Segment::A, Segment::D, Segment::E, Segment::F, Segment::G] {
+    ///     assert_eq!(color(seg), Some(Rgb565::BLUE));
+    /// }
+    /// for seg in [Segment::B, Segment::C] {
+    ///     assert_eq!(color(seg), Some(Rgb565::RED));
+    /// }
+    /// ```
+    pub fn with_off_color(mut self, off_color: C) -> Self {
+        self.off_color = Some(off_color);
+        self
+    }
 
-        let draw_width = if point {
-            let p_width = (size.width as f32 * self.point_width_rate).ceil() as u32;
-            all_area_width - size.width + p_width
-        } else {
-            all_area_width
-        };
-        Ok(draw_width)
+    /// 小数点とコロンを、数字と別の色で描画します。(初期値 None)
+    ///
+    /// Noneの場合は、数字と同じ文字色で描画します。時計の点滅するコロンを、
+    /// 数字より暗い色にする場合などに使えます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb565::RED)
+    ///     .with_separator_color(Some(Rgb565::BLUE));
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// Text::with_baseline("8.", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 数字のセルは文字色、小数点のセルは区切りの色だけで描画される
+    /// let colors = |x_range: core::ops::Range<i32>| {
+    ///     display
+    ///         .affected_area()
+    ///         .points()
+    ///         .filter(move |p| x_range.contains(&p.x))
+    ///         .filter_map(|p| display.get_pixel(p))
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert!(colors(0..10).iter().all(|&c| c == Rgb565::RED));
+    /// assert!(!colors(10..20).is_empty());
+    /// assert!(colors(10..20).iter().all(|&c| c == Rgb565::BLUE));
+    /// ```
+    pub fn with_separator_color(mut self, color: Option<C>) -> Self {
+        self.separator_color = color;
+        self
     }
 
-    fn calc_point_width(&self) -> u32 {
-        let left_margin: f32 = self.size.width as f32 * self.left_margin_rate;
-        let p_width = (self.size.width as f32 - left_margin * 2.0) * self.point_width_rate;
-        ((p_width + left_margin * 2.0).ceil() as u32) + 1
+    /// 小数点を、背景色で描画するかを指定します。(初期値 false)
+    ///
+    /// trueにすると、小数点の位置と幅はそのままで、小数点の丸を背景色で描画します。
+    /// 点滅させる小数点を、隣の数字の位置を変えずに、その場で消すのに使えます。
+    /// with_attached_pointで数字に重ねた小数点も、背景色で上書きします。
+    /// 背景色を指定していない場合は、小数点を描画しません。背景のグラデーションを
+    /// 指定した場合は、上端と下端の中間の色で描画します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, text::renderer::CharacterStyle, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_attached_point(true);
+    /// font.set_background_color(Some(BinaryColor::Off));
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// Text::with_baseline("1.2", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 点灯していた小数点を、同じ位置で消す
+    /// let next = Text::with_baseline("1.2", Point::zero(), font.with_point_as_background(true), Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// expected.set_allow_overdraw(true);
+    /// let expected_next = Text::with_baseline("12", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// ```
+    pub fn with_point_as_background(mut self, hidden: bool) -> Self {
+        self.point_as_background = hidden;
+        self
     }
-}
 
-impl<C: PixelColor> CharacterStyle for Font7Seg<C> {
-    type Color = C;
-    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
-        if let Some(color) = text_color {
-            self.text_color = color;
-        }
+    /// 点灯するセグメントの内側を、指定した色で描きます。(初期値 None)
+    ///
+    /// 各セグメントを文字色で描画した後、セグメントの太さの1/4だけ内側に縮めた形を、
+    /// この色で重ねて描画します。文字色より明るい色を指定すると、立体的に浮き出た
+    /// LEDのような見た目になります。色は混ぜないので、どの色の型でも使えます。
+    /// セグメントが細すぎて縮められない場合や、SegmentStyle::Strokeの場合は描画しません。
+    /// 小数点、コロン、度記号には描画しません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(40, 80), Rgb565::RED).with_highlight(Some(Rgb565::YELLOW));
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// let colors: Vec<_> = display
+    ///     .affected_area()
+    ///     .points()
+    ///     .filter_map(|p| display.get_pixel(p))
+    ///     .collect();
+    /// assert!(colors.contains(&Rgb565::RED));
+    /// assert!(colors.contains(&Rgb565::YELLOW));
+    /// ```
+    pub fn with_highlight(mut self, color: Option<C>) -> Self {
+        self.highlight = color;
+        self
     }
-    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
-        self.background_color = background_color;
+
+    /// 点灯したセグメントの一行おきの行を、colorで描画します。(初期値 None)
+    ///
+    /// ブラウン管の走査線のような見た目になります。数字の上端(マージンを除く)から数えて、
+    /// 2行目、4行目…を、文字色の代わりにこの色で描画します。小数点やコロンも同じです。
+    /// 背景と、消灯したセグメントには影響しません。行は、回転や傾けの前の、字形の行です。
+    /// Noneの場合は、すべての行を文字色で描画します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(20, 40), Rgb565::GREEN).with_scanlines(Some(Rgb565::BLACK));
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// Text::with_baseline("1", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // セグメントBの中央の列を、上から順に見ると、行ごとに色が入れ替わる
+    /// let area = display.affected_area();
+    /// let x = area.center().x;
+    /// let column: Vec<_> = (area.top_left.y + 4..area.center().y - 4)
+    ///     .map(|y| display.get_pixel(Point::new(x, y)).unwrap())
+    ///     .collect();
+    /// assert!(column.len() > 4);
+    /// assert!(column.windows(2).all(|w| w[0] != w[1]));
+    /// assert!(column.contains(&Rgb565::GREEN) && column.contains(&Rgb565::BLACK));
+    /// ```
+    pub fn with_scanlines(mut self, color: Option<C>) -> Self {
+        self.scanlines = color;
+        self
     }
-}
 
-impl<C: PixelColor> TextRenderer for Font7Seg<C> {
-    type Color = C;
-    fn draw_string<D>(
-        &self,
-        text: &str,
-        pos: Point,
-        _baseline: Baseline,
-        target: &mut D,
-    ) -> Result<Point, D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        let mut cur_pos = pos;
-        for c in text.chars() {
-            let mut num_target = target.cropped(&Rectangle::new(cur_pos, self.size));
-            if let Some(bg_color) = self.background_color {
-                num_target.clear(bg_color)?;
-            }
-            let w = if c.is_ascii_digit() {
-                let num = c.to_digit(10).unwrap();
-                self.draw_number(num, false, &mut num_target)?
-            } else if c == '.' {
-                self.draw_number(0, true, &mut num_target)?
-            } else {
-                0
-            };
-            cur_pos += Size::new(w, 0);
-        }
-        Ok(cur_pos)
+    /// 各文字のセルの枠と、マージンを除いた描画領域の枠を、指定した色で描きます。
+    /// (初期値 None)
+    ///
+    /// with_marginsやset_top_margin_rate、set_left_margin_rateでマージンを調整するときの、確認用です。
+    /// 文字列の描画で、一文字描画するごとに、字形の上から枠を重ねて描きます。
+    /// 枠は、傾きや左右反転を考えない、セルの長方形です。
+    pub fn with_debug_cells(mut self, color: Option<C>) -> Self {
+        self.debug_cells = color;
+        self
     }
 
-    fn draw_whitespace<D>(
-        &self,
-        width: u32,
-        pos: Point,
-        _baseline: Baseline,
-        _target: &mut D,
-    ) -> Result<Point, D::Error>
-    where
-        D: DrawTarget<Color = C>,
-    {
-        Ok(pos + Size::new(self.size.width * width, 0))
+    /// 先行する0を表示しないようにします。(初期値 false)
+    ///
+    /// trueにすると、"007"は"  7"のように、後ろに数字が続く先頭の0を、
+    /// 一桁分の空白として描画します。"0"や"0.5"の0のように、
+    /// 意味のある0は表示します。先頭の空白や符号("-"、"+")の後ろの0も対象です。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let plain = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let font = plain.with_blank_leading_zeros(true);
+    /// let draw = |font: Font7Seg<BinaryColor>, text| {
+    ///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///     let next = Text::with_baseline(text, Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     (display, next)
+    /// };
+    /// // "007"の先頭の0二つは、空白のセルになる
+    /// let (display, next) = draw(font, "007");
+    /// let (expected, expected_next) = draw(plain, "  7");
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// // "0"と"0.5"の0は、そのまま表示する
+    /// draw(font, "0").0.assert_eq(&draw(plain, "0").0);
+    /// draw(font, "0.5").0.assert_eq(&draw(plain, "0.5").0);
+    /// // 符号の後ろの0も消す
+    /// draw(font, "-05").0.assert_eq(&draw(plain, "- 5").0);
+    /// ```
+    pub fn with_blank_leading_zeros(mut self, blank: bool) -> Self {
+        self.blank_leading_zeros = blank;
+        self
     }
 
-    fn measure_string(&self, text: &str, pos: Point, _baseline: Baseline) -> TextMetrics {
-        let mut width = 0;
-        for c in text.chars() {
-            if c.is_ascii_digit() {
-                width += self.size.width;
-            } else if c == '.' {
-                width += self.calc_point_width();
-            }
-        }
-        let bounding_box = Rectangle::new(pos, Size::new(width, self.size.height));
-        TextMetrics {
-            bounding_box,
-            next_position: pos + Size::new(width, 0),
-        }
+    /// 数字の直後の小数点を、その数字のセルの右下に重ねて描画します。(初期値 false)
+    ///
+    /// trueにすると、数字の直後の小数点は、幅を取らずに直前の数字の右下隅に描画され、
+    /// カーソルも進みません。文字列の先頭や、数字以外の文字の後ろの小数点は、
+    /// 従来どおり、独立した幅の狭いセルに描画します。
+    pub fn with_attached_point(mut self, attached: bool) -> Self {
+        self.attached_point = attached;
+        self
     }
 
-    fn line_height(&self) -> u32 {
-        self.size.height
+    /// 文字と文字の間に入れる間隔を、ピクセル単位で指定します。(初期値 0)
+    ///
+    /// 間隔は、描画する文字の間にだけ入り、最後の文字の後ろには入りません。
+    /// draw_whitespaceで続けて空白を描画する場合も、空白と空白の間にだけ入るので、
+    /// 空白だけの文字列をdraw_stringやmeasure_stringで扱ったときと同じ幅だけ進みます。
+    /// 背景色を指定した場合は、間隔の部分も背景色で塗ります。
+    /// 座標の範囲に収まらないほど大きな間隔を指定した場合、その文字列は描画しません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb565};
+    /// # use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+    /// # use embedded_graphics::text::renderer::CharacterStyle;
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// // 前の表示が残っている状態
+    /// Rectangle::new(Point::zero(), Size::new(34, 20))
+    ///     .into_styled(PrimitiveStyle::with_fill(Rgb565::GREEN))
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    ///
+    /// let mut font = Font7Seg::new(Size::new(10, 20), Rgb565::RED).with_char_spacing(2);
+    /// font.set_background_color(Some(Rgb565::BLACK));
+    /// Text::with_baseline("1 2", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 空白や文字間の間隔にも、前の表示は残らない
+    /// assert!(display.affected_area().points().all(|p| display.get_pixel(p) != Some(Rgb565::GREEN)));
+    /// ```
+    ///
+    /// measure_stringのnext_positionは、小数点を含む場合も、描画後のカーソル位置と一致します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// for gap in 0..5 {
+    ///     let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_char_spacing(gap);
+    ///     for text in ["1", "12", "1.5", "1.", "3.14"] {
+    ///         let measured = font.measure_string(text, Point::zero(), Baseline::Top);
+    ///         let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///         let drawn = Text::with_baseline(text, Point::zero(), font, Baseline::Top)
+    ///             .draw(&mut display)
+    ///             .unwrap();
+    ///         assert_eq!(measured.next_position, drawn);
+    ///     }
+    ///     // 間隔は、文字と文字の間にだけ入る
+    ///     let next = font.measure_string("12", Point::zero(), Baseline::Top).next_position;
+    ///     assert_eq!(next.x, 20 + gap as i32);
+    ///     // draw_whitespaceも、空白だけの文字列と同じ幅だけ進む
+    ///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///     for font in [font, font.with_scale(1.37)] {
+    ///         for count in 1..4 {
+    ///             let spaces = &"   "[..count as usize];
+    ///             let measured = font.measure_string(spaces, Point::zero(), Baseline::Top);
+    ///             let drawn = font
+    ///                 .draw_whitespace(count, Point::zero(), Baseline::Top, &mut display)
+    ///                 .unwrap();
+    ///             assert_eq!(drawn, measured.next_position);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// // 極端に大きな間隔でも、パニックしない
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_char_spacing(u32::MAX);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.measure_string("88", Point::zero(), Baseline::Top);
+    /// Text::with_baseline("88", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// font.draw_whitespace(2, Point::zero(), Baseline::Top, &mut display)
+    ///     .unwrap();
+    /// ```
+    pub fn with_char_spacing(mut self, gap: u32) -> Self {
+        self.char_spacing = gap;
+        self
+    }
+
+    /// 行と行の間に入れる間隔を、ピクセル単位で指定します。(初期値 0)
+    ///
+    /// line_height()は、数字の高さにこの間隔を加えた値になります。複数行のTextで、
+    /// 上下の行の数字が接しないようにできます。数字の大きさと、measure_stringの
+    /// bounding_boxの高さは変わりません。
+    /// line_height()は、TextのLineHeight::Percent(100)で溢れない値(u32::MAX / 100)で
+    /// 頭打ちになります。with_line_heightで指定した場合も同じです。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_line_spacing(4);
+    /// assert_eq!(font.line_height(), 24);
+    /// let metrics = font.measure_string("12", Point::zero(), Baseline::Top);
+    /// assert_eq!(metrics.bounding_box.size.height, 20);
+    ///
+    /// // 極端に大きな間隔でも、複数行のTextはパニックしない
+    /// let font = font.with_line_spacing(u32::MAX);
+    /// assert_eq!(font.line_height(), u32::MAX / 100);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("12\n34", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// ```
+    pub fn with_line_spacing(mut self, extra: u32) -> Self {
+        self.line_spacing = extra;
+        self
+    }
+
+    /// line_height()が返す行の高さを、ピクセル単位で指定します。(初期値 数字の高さ)
+    ///
+    /// 指定すると、with_line_spacingの間隔は使わず、この値をそのまま返します。
+    /// 数字の高さより小さくすると、複数行のTextで、上下の行を詰めて並べられます。
+    /// 数字の大きさと、measure_stringのbounding_boxの高さは変わりません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_line_height(16);
+    /// assert_eq!(font.line_height(), 16);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// Text::with_baseline("12\n34", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    ///
+    /// // 二行目は、16ピクセル下から描画される
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// expected.set_allow_overdraw(true);
+    /// for (text, y) in [("12", 0), ("34", 16)] {
+    ///     Text::with_baseline(text, Point::new(0, y), font, Baseline::Top)
+    ///         .draw(&mut expected)
+    ///         .unwrap();
+    /// }
+    /// display.assert_eq(&expected);
+    /// ```
+    pub fn with_line_height(mut self, height: u32) -> Self {
+        self.line_height = Some(height);
+        self
+    }
+
+    /// 数字を、斜体のように傾けて描画します。(初期値 0.0)
+    /// * `degrees` - 傾ける角度(度)。正で右に、負で左に傾きます。
+    ///
+    /// -30〜30度の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    /// 傾けた分だけ、数字はセルの右側にはみ出します。measure_stringの
+    /// bounding_boxは、はみ出した部分を含みますが、カーソルの進む幅は変わりません。
+    pub fn with_slant(mut self, degrees: f32) -> Self {
+        let degrees = if degrees.is_nan() {
+            0.0
+        } else {
+            degrees.clamp(-30.0, 30.0)
+        };
+        self.slant = tan_approx(degrees.to_radians());
+        self
+    }
+
+    /// 実物の表示器のように、縦のセグメント(B,C,E,F)だけを傾けます。(初期値 0.0)
+    /// * `degrees` - 傾ける角度(度)。正で右に、負で左に傾きます。
+    ///
+    /// with_slantと違い、横のセグメント(A,D,G)は形を変えずに、縦のセグメントの端に
+    /// 合うよう水平にずらすだけです。-15〜15度の範囲外の値は、範囲内に丸め込みます。
+    /// NaNは0.0とみなします。SegmentShape::Pointedの場合のみ有効です。
+    /// 傾けた分だけ、数字はセルの右側にはみ出し、measure_stringのbounding_boxも、
+    /// その分だけ広がります。カーソルの進む幅は変わりません。
+    pub fn with_segment_tilt(mut self, degrees: f32) -> Self {
+        let degrees = if degrees.is_nan() {
+            0.0
+        } else {
+            degrees.clamp(-15.0, 15.0)
+        };
+        self.segment_tilt = tan_approx(degrees.to_radians());
+        self
+    }
+
+    /// 数字を左右反転して描画するかを指定します。(初期値 false)
+    ///
+    /// trueにすると、各数字の字形を左右反転し、小数点は数字の左下に付きます。
+    /// draw_stringでは、文字の並びも逆になり、文字列全体を鏡に映した形になります。
+    /// 文字列の占める範囲と、戻り値の次の文字の位置は、反転しない場合と同じです。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let draw = |font: Font7Seg<BinaryColor>| {
+    ///     let mut display = MockDisplay::new();
+    ///     Text::with_baseline("2", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     display
+    /// };
+    /// let plain = draw(font);
+    /// // 反転しない「2」を、セルの縦の中心線で折り返したもの
+    /// let mut reflected = MockDisplay::new();
+    /// for p in plain.affected_area().points() {
+    ///     if let Some(color) = plain.get_pixel(p) {
+    ///         reflected.set_pixel(Point::new(9 - p.x, p.y), Some(color));
+    ///     }
+    /// }
+    /// draw(font.with_mirror_x(true)).assert_eq(&reflected);
+    /// ```
+    pub fn with_mirror_x(mut self, mirror_x: bool) -> Self {
+        self.mirror_x = mirror_x;
+        self
+    }
+
+    /// 描画する文字列を、時計回りに回転します。(初期値 Rotation::Deg0)
+    ///
+    /// 文字列全体を回転しない場合の範囲に描いてから、その範囲の左上隅を基準に回転します。
+    /// 横向きに取り付けた表示器に、縦に読む数字を描く場合などに使えます。
+    /// Deg90とDeg270では、measure_stringのbounding_boxの幅と高さが入れ替わり、描画後の
+    /// カーソルは、回転後の幅(数字の高さ)だけ右へ進みます。一文字を描画するdraw_digitなどや、
+    /// draw_string_verticalでは、一文字ずつセルの中で回転します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, Rotation};
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.draw_digit(7, Point::zero(), &mut display).unwrap();
+    ///
+    /// let mut rotated: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let area = font
+    ///     .with_rotation(Rotation::Deg90)
+    ///     .draw_digit(7, Point::zero(), &mut rotated)
+    ///     .unwrap();
+    /// assert_eq!(area.size, Size::new(20, 10));
+    /// // 回転しない"7"の各点(x, y)は、回転すると(19 - y, x)になる
+    /// for y in 0..20 {
+    ///     for x in 0..10 {
+    ///         assert_eq!(display.get_pixel(Point::new(x, y)), rotated.get_pixel(Point::new(19 - y, x)));
+    ///     }
+    /// }
+    /// ```
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// "1"を、幅の狭いセルで描画するかを指定します。(初期値 false)
+    ///
+    /// trueにすると、右側の縦のセグメント(BとC)だけを点灯する字形(通常は"1")の幅を、
+    ///
+    /// `2 × ceil(幅 × left_margin_rate) + ceil((幅 - 2 × ceil(幅 × left_margin_rate)) × line_width_rate)`
+    ///
+    /// ピクセル(左右のマージンとセグメントの太さ)にします。他の文字の幅は変わりません。
+    /// draw_stringとmeasure_stringは、同じ幅を使います。
+    pub fn with_proportional(mut self, proportional: bool) -> Self {
+        self.proportional = proportional;
+        self
+    }
+
+    /// measure_stringだけで、"1"を幅の狭いセルとして数えるかを指定します。(初期値 false)
+    ///
+    /// trueにすると、measure_stringのnext_positionとbounding_boxは、with_proportional(true)の
+    /// 場合と同じ幅になります。描画には影響しません。draw_stringなどは、"1"も通常の幅の
+    /// セルに描画し、描画後のカーソル位置も通常の幅で進みます。実際のLEDモジュールのように、
+    /// "1"を右寄せで描画したまま、表の配置だけを詰めたい場合に使います。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let narrow = font.with_measure_narrow_one(true);
+    /// let proportional = font.with_proportional(true);
+    /// let measure = |f: Font7Seg<BinaryColor>| f.measure_string("11", Point::zero(), Baseline::Top);
+    /// assert_eq!(measure(narrow), measure(proportional));
+    /// assert!(measure(narrow).next_position.x < measure(font).next_position.x);
+    ///
+    /// // 描画は、指定しない場合と同じ
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = Text::with_baseline("11", Point::zero(), narrow, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let expected_next = Text::with_baseline("11", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// ```
+    pub fn with_measure_narrow_one(mut self, narrow: bool) -> Self {
+        self.measure_narrow_one = narrow;
+        self
+    }
+
+    /// セグメントの両端の形を指定します。(初期値 SegmentShape::Pointed)
+    ///
+    /// アンチエイリアスは、SegmentShape::Pointedの場合のみ有効です。
+    pub fn with_segment_shape(mut self, shape: SegmentShape) -> Self {
+        self.segment_shape = shape;
+        self
+    }
+
+    /// セグメントの中央の長方形の角を、半径radiusピクセルで丸めます。(初期値 0)
+    ///
+    /// 0では、角を丸めません。長方形を描くSegmentShape::FlatとRoundedで有効です。
+    /// 半径が長方形の幅や高さの半分を超える場合は、収まる大きさに縮めます。
+    /// 丸めた角は長方形の内側に収まるので、セグメントがセルからはみ出すことはありません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, Segment, SegmentShape};
+    /// let font = Font7Seg::new(Size::new(20, 40), BinaryColor::On)
+    ///     .with_segment_shape(SegmentShape::Flat);
+    /// let mut sharp: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.draw_segments(&[Segment::G], Point::zero(), &mut sharp).unwrap();
+    ///
+    /// let mut rounded: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.with_corner_radius(2)
+    ///     .draw_segments(&[Segment::G], Point::zero(), &mut rounded)
+    ///     .unwrap();
+    /// let area = sharp.affected_area();
+    /// // 角だけが欠け、範囲は丸めない場合を超えない
+    /// assert_eq!(rounded.affected_area(), area);
+    /// assert_eq!(sharp.get_pixel(area.top_left), Some(BinaryColor::On));
+    /// assert_eq!(rounded.get_pixel(area.top_left), None);
+    /// ```
+    pub fn with_corner_radius(mut self, radius: u32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// セグメントと小数点・コロンを、塗りつぶすか、輪郭だけを描くかを指定します。
+    /// (初期値 SegmentStyle::Fill)
+    ///
+    /// SegmentStyle::Strokeでは、各形の内側を、指定した幅の輪郭だけで描きます。
+    /// SegmentShape::FlatとRoundedでは、長方形と両端の円の輪郭を別々に描くので、
+    /// 継ぎ目の線が残ります。見た目は、Pointedが最もきれいです。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, Segment, SegmentStyle};
+    /// let font = Font7Seg::new(Size::new(40, 80), BinaryColor::On)
+    ///     .with_segment_style(SegmentStyle::Stroke { width: 1 });
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.draw_segments(&[Segment::G], Point::zero(), &mut display).unwrap();
+    /// let area = display.affected_area();
+    /// // 輪郭は描くが、セグメントの内側は描かない
+    /// assert_eq!(display.get_pixel(area.top_left + Point::new(area.size.width as i32 / 2, 0)), Some(BinaryColor::On));
+    /// assert_eq!(display.get_pixel(area.center()), None);
+    /// ```
+    pub fn with_segment_style(mut self, style: SegmentStyle) -> Self {
+        self.segment_style = style;
+        self
+    }
+
+    /// 各セグメントを、LEDを並べたような点の列で描画します。
+    /// * `dot_diameter` - 点の直径(ピクセル単位)。0の場合は1として扱います。
+    /// * `spacing`      - 隣り合う点の間の隙間(ピクセル単位)
+    ///
+    /// 点は、セグメントの中心線に沿って、セグメントの両端から同じだけ離れるように並べます。
+    /// 点の塗り方は、with_segment_styleの指定に従います。小数点やコロンは、そのままです。
+    /// セグメントの内側を縮めて描くwith_highlightは、点の列には描きません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(20, 40), BinaryColor::On).with_dot_segments(3, 2);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("1", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // "1"は、BとCのセグメントが、それぞれ縦に並んだ3つの点の列になる
+    /// let area = display.affected_area();
+    /// let x = area.center().x;
+    /// let mut dots = 0;
+    /// let mut lit = false;
+    /// for y in area.rows() {
+    ///     let on = display.get_pixel(Point::new(x, y)).is_some();
+    ///     if on && !lit {
+    ///         dots += 1;
+    ///     }
+    ///     lit = on;
+    /// }
+    /// assert_eq!(dots, 3 * 2);
+    /// ```
+    pub fn with_dot_segments(mut self, dot_diameter: u32, spacing: u32) -> Self {
+        self.dot_segments = Some((dot_diameter.max(1), spacing));
+        self
+    }
+
+    /// 小数点の縦の位置を指定します。(初期値 PointPosition::Bottom)
+    ///
+    /// Middleにすると、"1·000"のような桁区切りの点として使えます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, PointPosition};
+    /// // セルの高さを3等分し、点がそれぞれ上・中・下の範囲に収まることを確かめる
+    /// let positions = [PointPosition::Top, PointPosition::Middle, PointPosition::Bottom];
+    /// for (third, position) in positions.into_iter().enumerate() {
+    ///     let font = Font7Seg::new(Size::new(12, 30), BinaryColor::On).with_point_position(position);
+    ///     let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    ///     Text::with_baseline(".", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     let area = display.affected_area();
+    ///     let top = third as i32 * 10;
+    ///     assert!(area.size.height > 0);
+    ///     assert!(area.rows().all(|y| (top..top + 10).contains(&y)));
+    /// }
+    /// ```
+    pub fn with_point_position(mut self, position: PointPosition) -> Self {
+        self.point_position = position;
+        self
+    }
+
+    /// 小数点の形を指定します。(初期値 PointShape::Circle)
+    ///
+    /// 小さなサイズの単色の表示器では、丸がいびつな形になります。PointShape::Squareにすると、
+    /// 丸と同じ範囲(幅は、数字の幅とpoint_width_rateから決まります)を、正方形で塗ります。
+    /// カンマとコロンの丸は、変わりません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use font_7seg::{Font7Seg, PointShape};
+    /// let font = Font7Seg::new(Size::new(14, 28), BinaryColor::On)
+    ///     .with_point_shape(PointShape::Square);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline(".", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 丸では角が欠ける3x3の範囲を、隙間なく塗る
+    /// let area = display.affected_area();
+    /// assert_eq!(area, Rectangle::new(Point::new(1, 22), Size::new(3, 3)));
+    /// assert!(area.points().all(|p| display.get_pixel(p) == Some(BinaryColor::On)));
+    /// ```
+    pub fn with_point_shape(mut self, shape: PointShape) -> Self {
+        self.point_shape = shape;
+        self
+    }
+
+    /// draw_stringなどの原点posを、文字列のどこに置くかを指定します。(初期値 Origin::TopLeft)
+    ///
+    /// Origin::Centerでは、measure_stringのbounding_boxの中央がposになるように描画します。
+    /// Baseline引数は使いません。回転した場合は、回転後の範囲の中央がposになります。
+    /// 描画後のカーソル位置は、文字列の右端の次の列で、高さはposと同じです。
+    /// draw_string_right_alignedとdraw_string_decimal_alignedは、この指定によらず、
+    /// 位置を決めます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use font_7seg::{Font7Seg, Origin};
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_origin(Origin::Center);
+    /// let center = Point::new(32, 32);
+    /// let metrics = font.measure_string("88", center, Baseline::Top);
+    /// assert_eq!(metrics.bounding_box, Rectangle::new(Point::new(23, 23), Size::new(20, 20)));
+    /// assert_eq!(metrics.bounding_box.center(), center);
+    ///
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = Text::with_baseline("88", center, font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(next, metrics.next_position);
+    /// let top_left = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("88", Point::new(23, 23), top_left, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// ```
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// 隣り合うセグメントの間の隙間を、ピクセル単位で指定します。(初期値 0)
+    ///
+    /// 各セグメントの両端を、この幅だけ短くします。実物の表示器のように、
+    /// セグメントの継ぎ目に背景が見えるようになります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::CharacterStyle}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let draw = |gap: u32| {
+    ///     let mut font = Font7Seg::new(Size::new(20, 40), BinaryColor::On).with_segment_gap(gap);
+    ///     font.set_background_color(Some(BinaryColor::Off));
+    ///     let mut display = MockDisplay::new();
+    ///     display.set_allow_overdraw(true);
+    ///     Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     display
+    /// };
+    /// // 「8」の、横のセグメントと縦のセグメントの継ぎ目
+    /// let joints = [(4, 3), (15, 3), (4, 19), (15, 19), (4, 36), (15, 36)].map(|(x, y)| Point::new(x, y));
+    /// let (solid, gapped) = (draw(0), draw(2));
+    /// for p in joints {
+    ///     assert_eq!(solid.get_pixel(p), Some(BinaryColor::On));
+    ///     assert_eq!(gapped.get_pixel(p), Some(BinaryColor::Off));
+    /// }
+    /// ```
+    pub fn with_segment_gap(mut self, pixels: u32) -> Self {
+        self.segment_gap = pixels;
+        self
+    }
+
+    /// 0〜9の各数字で点灯させるセグメントを指定します。
+    /// * `pats` - pats[n]が数字nのセグメントパターン
+    ///
+    /// 各パターンは、bit0=A、bit1=B、…、bit6=Gの7ビットです(各セグメントの位置は、
+    /// Segmentの説明の図のとおり)。bit7は使わないため、無視します。
+    /// A〜Fの字形は変わりません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let mut pats = [0b0011_1111, 0b0000_0110, 0b0101_1011, 0b0100_1111, 0b0110_0110,
+    ///                 0b0110_1101, 0b0111_1101, 0b0010_0111, 0b0111_1111, 0b0110_1111];
+    /// pats[7] = 0b0010_0011; // Fのセグメントも点灯させる7
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_segment_patterns(pats);
+    /// ```
+    pub fn with_segment_patterns(mut self, pats: [u8; 10]) -> Self {
+        for (dst, pat) in self.seg_pats.iter_mut().zip(pats) {
+            *dst = pat & 0b0111_1111;
+        }
+        self
+    }
+
+    /// 文字列を描画し、未対応のため無視した文字の数も返します。
+    ///
+    /// 描画の仕方は、draw_stringと同じです。戻り値は、描画後のカーソル位置と、
+    /// 無視した文字数の組です。"12,5"のような、意図しない文字の混入を検出できます。
+    pub fn draw_string_checked<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<(Point, usize), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_text(text, pos, baseline, None, target)
+    }
+
+    /// 点滅する表示のために、visibleがfalseのときは文字列を消して描画します。
+    ///
+    /// visibleがtrueの場合は、draw_stringと同じです。falseの場合は、すべてのセグメントを
+    /// 消灯したように描画します。消灯セグメントの色があればその色で、無ければ背景色で
+    /// セグメントを塗り、どちらも無ければ何も描画しません。いずれの場合も、
+    /// 戻り値のカーソル位置は、visibleによらず同じなので、後に続く文字の位置は変わりません。
+    /// 点滅の周期は、呼び出し側で管理してください。
+    pub fn draw_string_blink<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        visible: bool,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if visible {
+            return self.draw_string(text, pos, baseline, target);
+        }
+        match self.off_color.or(self.background_color) {
+            Some(color) => {
+                // 点灯するセグメントも、消灯時の色で描いて、字形を消す
+                let mut hidden = *self;
+                hidden.text_color = color;
+                hidden.separator_color = None;
+                hidden.highlight = None;
+                hidden.brightness = None;
+                hidden.draw_string(text, pos, baseline, target)
+            }
+            None => Ok(self.drawn_metrics(text, pos, baseline).next_position),
+        }
+    }
+
+    /// draw_stringで文字列を描画したときに、実際に塗られるピクセルを囲む最小の長方形を
+    /// 返します。
+    ///
+    /// measure_stringのbounding_boxはマージンを含むセル全体ですが、こちらは、傾きで
+    /// はみ出す部分や小数点の幅も含めて、実際に塗る範囲だけを返します。背景色を指定した
+    /// 場合は、背景を塗る範囲も含みます。部分的に書き換える表示器の、書き換える範囲の
+    /// 計算に使えます。実際に描画先を使わずに描画して求めるので、描画と同じだけの時間が
+    /// かかります。何も塗らない場合は、posを左上隅とする大きさ0の長方形を返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_slant(10.0);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("1.8", Point::new(2, 3), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     font.bounding_rect("1.8", Point::new(2, 3), Baseline::Top),
+    ///     display.affected_area()
+    /// );
+    /// ```
+    pub fn bounding_rect(&self, text: &str, pos: Point, baseline: Baseline) -> Rectangle {
+        let mut bounds = BoundsTarget::new();
+        match self.draw_text(text, pos, baseline, None, &mut bounds) {
+            Ok(_) => bounds.rect().unwrap_or(Rectangle::new(pos, Size::zero())),
+            Err(never) => match never {},
+        }
+    }
+
+    /// 文字列を、描画先の既存の内容に、with_opacityの割合で半透明に重ねて描画します。
+    ///
+    /// 描画する各ピクセル(背景色を指定した場合は背景も含みます)を、描画先から読み出した
+    /// 色と混ぜて描画します。描画先は、描画済みの色を読み出せるReadPixelを実装している
+    /// 必要があります。読み出せないピクセルや、with_opacityを指定していない場合は、
+    /// draw_stringと同じく、不透明に描画します。通常のdraw_string(Text経由の描画)は、
+    /// 描画先を読み出せないため、常に不透明です。
+    ///
+    /// 次の例では、MockDisplayを包んだ型に、ReadPixelを実装しています。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::Baseline, pixelcolor::Rgb888};
+    /// # use core::convert::Infallible;
+    /// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+    /// # use font_7seg::{Font7Seg, ReadPixel};
+    /// struct Screen(MockDisplay<Rgb888>);
+    /// impl Dimensions for Screen {
+    ///     fn bounding_box(&self) -> Rectangle {
+    ///         self.0.bounding_box()
+    ///     }
+    /// }
+    /// impl DrawTarget for Screen {
+    ///     type Color = Rgb888;
+    ///     type Error = Infallible;
+    ///     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    ///     where
+    ///         I: IntoIterator<Item = Pixel<Rgb888>>,
+    ///     {
+    ///         self.0.draw_iter(pixels)
+    ///     }
+    /// }
+    /// impl ReadPixel for Screen {
+    ///     fn read_pixel(&self, p: Point) -> Option<Rgb888> {
+    ///         self.0.bounding_box().contains(p).then(|| self.0.get_pixel(p)).flatten()
+    ///     }
+    /// }
+    /// # let mut display = Screen(MockDisplay::new());
+    /// # display.0.set_allow_overdraw(true);
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb888::WHITE).with_opacity(128);
+    /// display.fill_solid(&display.bounding_box(), Rgb888::RED).unwrap();
+    /// font.draw_string_blended("8", Point::zero(), Baseline::Top, &mut display)
+    ///     .unwrap();
+    /// // セグメントGの中央は、白と赤を半分ずつ混ぜた色
+    /// assert_eq!(display.read_pixel(Point::new(5, 10)), Some(Rgb888::new(255, 128, 128)));
+    /// // セグメントの外は、元の色のまま
+    /// assert_eq!(display.read_pixel(Point::new(5, 5)), Some(Rgb888::RED));
+    /// ```
+    ///
+    /// 一つのセグメントの中では、各ピクセルを一度だけ描画するので、SegmentShape::Roundedの
+    /// 丸い端と中央の長方形の境目も、同じ色に混ざります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::Baseline, pixelcolor::Rgb888};
+    /// # use font_7seg::SegmentShape;
+    /// # use core::convert::Infallible;
+    /// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+    /// # use font_7seg::{Font7Seg, ReadPixel};
+    /// # struct Screen(MockDisplay<Rgb888>);
+    /// # impl Dimensions for Screen {
+    /// #     fn bounding_box(&self) -> Rectangle {
+    /// #         self.0.bounding_box()
+    /// #     }
+    /// # }
+    /// # impl DrawTarget for Screen {
+    /// #     type Color = Rgb888;
+    /// #     type Error = Infallible;
+    /// #     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    /// #     where
+    /// #         I: IntoIterator<Item = Pixel<Rgb888>>,
+    /// #     {
+    /// #         self.0.draw_iter(pixels)
+    /// #     }
+    /// # }
+    /// # impl ReadPixel for Screen {
+    /// #     fn read_pixel(&self, p: Point) -> Option<Rgb888> {
+    /// #         self.0.bounding_box().contains(p).then(|| self.0.get_pixel(p)).flatten()
+    /// #     }
+    /// # }
+    /// # let mut display = Screen(MockDisplay::new());
+    /// # display.0.set_allow_overdraw(true);
+    /// let font = Font7Seg::new(Size::new(20, 40), Rgb888::WHITE)
+    ///     .with_segment_shape(SegmentShape::Rounded)
+    ///     .with_opacity(128);
+    /// display.fill_solid(&display.bounding_box(), Rgb888::BLACK).unwrap();
+    /// font.draw_string_blended("8", Point::zero(), Baseline::Top, &mut display)
+    ///     .unwrap();
+    /// // 重ねた部分は、どこも白と黒を半分ずつ混ぜた灰色で、二度混ぜた明るい色は無い
+    /// let gray = Rgb888::new(128, 128, 128);
+    /// let mut colors = display.0.affected_area().points().filter_map(|p| display.read_pixel(p));
+    /// assert!(colors.all(|c| c == Rgb888::BLACK || c == gray));
+    /// ```
+    ///
+    /// ただし、背景色を指定した場合は、背景を塗ったピクセルに、さらにセグメントを描画します。
+    /// セグメントの部分は、背景を混ぜた色に、さらに文字色を混ぜた色になります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, renderer::CharacterStyle}, pixelcolor::Rgb888};
+    /// # use core::convert::Infallible;
+    /// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+    /// # use font_7seg::{Font7Seg, ReadPixel};
+    /// # struct Screen(MockDisplay<Rgb888>);
+    /// # impl Dimensions for Screen {
+    /// #     fn bounding_box(&self) -> Rectangle {
+    /// #         self.0.bounding_box()
+    /// #     }
+    /// # }
+    /// # impl DrawTarget for Screen {
+    /// #     type Color = Rgb888;
+    /// #     type Error = Infallible;
+    /// #     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    /// #     where
+    /// #         I: IntoIterator<Item = Pixel<Rgb888>>,
+    /// #     {
+    /// #         self.0.draw_iter(pixels)
+    /// #     }
+    /// # }
+    /// # impl ReadPixel for Screen {
+    /// #     fn read_pixel(&self, p: Point) -> Option<Rgb888> {
+    /// #         self.0.bounding_box().contains(p).then(|| self.0.get_pixel(p)).flatten()
+    /// #     }
+    /// # }
+    /// # let mut display = Screen(MockDisplay::new());
+    /// # display.0.set_allow_overdraw(true);
+    /// let mut font = Font7Seg::new(Size::new(10, 20), Rgb888::WHITE).with_opacity(128);
+    /// font.set_background_color(Some(Rgb888::BLACK));
+    /// display.fill_solid(&display.bounding_box(), Rgb888::RED).unwrap();
+    /// font.draw_string_blended("8", Point::zero(), Baseline::Top, &mut display)
+    ///     .unwrap();
+    /// // セグメントの外は、赤と黒を半分ずつ混ぜた色
+    /// assert_eq!(display.read_pixel(Point::new(5, 5)), Some(Rgb888::new(127, 0, 0)));
+    /// // セグメントGの中央は、その色に、さらに白を半分混ぜた色
+    /// assert_eq!(display.read_pixel(Point::new(5, 10)), Some(Rgb888::new(191, 128, 128)));
+    /// ```
+    pub fn draw_string_blended<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: ReadPixel<Color = C>,
+    {
+        match self.mix {
+            Some(mix) if self.opacity < 255 => {
+                let mut blended = Blended::new(target, self.opacity, mix);
+                self.draw_string(text, pos, baseline, &mut blended)
+            }
+            _ => self.draw_string(text, pos, baseline, target),
+        }
+    }
+
+    /// 文字列を、フォントと異なる色の型の描画先に、色を変換しながら描画します。
+    ///
+    /// 文字色や背景色など、描画する各ピクセルの色を、`Into<D::Color>`で描画先の色に
+    /// 変換します。Rgb888のフォントを、Rgb565の表示器に描画する場合などに使います。
+    /// 変換の結果は、embedded_graphicsの色の型の間のFromの実装に従います。
+    /// 描画の仕方は、draw_stringと同じです。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::{Rgb565, Rgb888}};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb888::RED);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// font.draw_string_converted("12", Point::zero(), Baseline::Top, &mut display)
+    ///     .unwrap();
+    ///
+    /// let mut expected: MockDisplay<Rgb565> = MockDisplay::new();
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb565::RED);
+    /// Text::with_baseline("12", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// ```
+    pub fn draw_string_converted<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget,
+        C: Into<D::Color>,
+    {
+        self.draw_string(text, pos, baseline, &mut target.color_converted())
+    }
+
+    /// 文字列を描画します。未対応の文字を含む場合は、何も描画せずにエラーを返します。
+    ///
+    /// 描画の仕方は、draw_stringと同じです。エラーの場合、Font7SegError::UnsupportedCharに
+    /// 最初の未対応の文字が入るので、センサーなどから来た不正な文字列の記録に使えます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::Baseline, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, Font7SegError};
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let result = font.try_draw_string("12;5", Point::zero(), Baseline::Top, &mut display);
+    /// assert_eq!(result, Err(Font7SegError::UnsupportedChar(';')));
+    /// ```
+    pub fn try_draw_string<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, Font7SegError<D::Error>>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if let Some(c) = text.chars().find(|&c| !is_supported(c)) {
+            return Err(Font7SegError::UnsupportedChar(c));
+        }
+        self.draw_text(text, pos, baseline, None, target)
+            .map(|(next_pos, _)| next_pos)
+            .map_err(Font7SegError::Draw)
+    }
+
+    /// 文字列をdraw_stringと同じく描画し、描画先のエラーで途中で止まった場合は、
+    /// どこまで描画したかをエラーに入れて返します。
+    ///
+    /// 通信で描画する表示器などで、一時的なエラーで描画が途中で止まった場合に、
+    /// PartialDrawのdrawnとpositionを使って、残りの文字を描画し直すのに使えます。
+    /// drawnの文字までは描画し終えていて、次の文字は途中まで描画されている場合があります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, PartialDraw};
+    /// // limitより多くのピクセルを描画しようとすると、エラーになる描画先
+    /// struct Flaky {
+    ///     display: MockDisplay<BinaryColor>,
+    ///     limit: usize,
+    /// }
+    /// impl OriginDimensions for Flaky {
+    ///     fn size(&self) -> Size {
+    ///         self.display.size()
+    ///     }
+    /// }
+    /// impl DrawTarget for Flaky {
+    ///     type Color = BinaryColor;
+    ///     type Error = ();
+    ///     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), ()>
+    ///     where
+    ///         I: IntoIterator<Item = Pixel<BinaryColor>>,
+    ///     {
+    ///         for pixel in pixels {
+    ///             self.limit = self.limit.checked_sub(1).ok_or(())?;
+    ///             pixel.draw(&mut self.display).unwrap();
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut ones: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let one_end = Text::with_baseline("1", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut ones)
+    ///     .unwrap();
+    /// let one_pixels = ones.affected_area().points().filter(|&p| ones.get_pixel(p).is_some()).count();
+    ///
+    /// // "1"を描き終えて、"2"の途中でエラーになる
+    /// let mut target = Flaky { display: MockDisplay::new(), limit: one_pixels + 5 };
+    /// let result = font.draw_string_resumable("123", Point::zero(), Baseline::Top, &mut target);
+    /// assert_eq!(result, Err(PartialDraw { error: (), drawn: 1, position: one_end }));
+    ///
+    /// // 残りの文字を、positionから描画し直す
+    /// let mut target = Flaky { display: target.display, limit: usize::MAX };
+    /// target.display.set_allow_overdraw(true);
+    /// font.draw_string_resumable("23", one_end, Baseline::Top, &mut target).unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("123", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// target.display.assert_eq(&expected);
+    /// ```
+    pub fn draw_string_resumable<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, PartialDraw<D::Error>>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyphs = self.glyphs(text);
+        match self.draw_glyph_line_tracked(glyphs.clone(), pos, baseline, &[], None, target) {
+            Ok((next_pos, _)) => Ok(next_pos),
+            Err((error, drawn)) => {
+                // 描画し終えた部分だけをdraw_stringした場合の、カーソル位置を求める。
+                let (_, text_width) = self.line_width(glyphs.clone().flatten());
+                let line_x = self.line_top_left(pos, baseline, text_width).x;
+                let (advance, _) = self.line_width(glyphs.take(drawn).flatten());
+                let position = self.next_position(Point::new(line_x, pos.y), advance);
+                Err(PartialDraw {
+                    error,
+                    drawn,
+                    position,
+                })
+            }
+        }
+    }
+
+    /// 符号なし整数を、10進数で描画します。
+    /// * `value`      - 描画する数値
+    /// * `min_digits` - 最小の桁数。足りない桁は、先頭を0で埋めます。(最大10桁)
+    /// * `pos`        - 左上隅の位置
+    /// * `target`     - 描画対象
+    ///
+    /// 文字列への変換は、スタック上のバッファで行うので、allocは不要です。
+    /// 戻り値は、draw_stringと同じく、描画後のカーソル位置です。
+    pub fn draw_u32<D>(
+        &self,
+        value: u32,
+        min_digits: u8,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut buf = [0; DECIMAL_BUF_LEN];
+        let text = format_decimal(value, false, min_digits, &mut buf);
+        self.draw_string(text, pos, Baseline::Top, target)
+    }
+
+    /// 符号付き整数を、10進数で描画します。負の数には、マイナス記号を付けます。
+    ///
+    /// min_digitsは符号を除いた桁数です。その他は、draw_u32と同じです。
+    pub fn draw_i32<D>(
+        &self,
+        value: i32,
+        min_digits: u8,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut buf = [0; DECIMAL_BUF_LEN];
+        let text = format_decimal(value.unsigned_abs(), value < 0, min_digits, &mut buf);
+        self.draw_string(text, pos, Baseline::Top, target)
+    }
+
+    /// 符号付き整数を、先頭に符号のセルを置いて、10進数で描画します。
+    /// * `value`  - 描画する数値
+    /// * `pos`    - 左上隅の位置
+    /// * `target` - 描画対象
+    ///
+    /// 符号のセルの幅は、`ceil(数字の幅 × sign_width_rate)`ピクセルです
+    /// (with_sign_width_rateで指定します。初期値 0.6)。負の数では、このセルに、
+    /// セグメントGを幅に合わせて短くしたマイナス記号を描き、0以上の数では、背景だけを
+    /// 塗ります。符号の有無で数字の位置が変わらないので、0をまたいで変化する値も、
+    /// 桁がずれずに表示できます。セルの幅は、マイナス記号の両端の三角形が収まる程度に
+    /// してください。狭すぎる場合は、はみ出す部分を描画しません。
+    ///
+    /// 戻り値は、draw_stringと同じく、描画後のカーソル位置です。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut negative: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = font.draw_signed(-42, Point::zero(), &mut negative).unwrap();
+    /// let mut positive: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next_positive = font.draw_signed(42, Point::zero(), &mut positive).unwrap();
+    /// // 符号のセルは6ピクセルで、符号によらず、数字は同じ位置に描画される
+    /// assert_eq!(next, Point::new(26, 0));
+    /// assert_eq!(next_positive, next);
+    /// let digits = Rectangle::new(Point::new(6, 0), Size::new(20, 20));
+    /// assert!(digits.points().all(|p| negative.get_pixel(p) == positive.get_pixel(p)));
+    /// // マイナス記号は、符号のセルの中に描画される
+    /// let sign = Rectangle::new(Point::zero(), Size::new(6, 20));
+    /// assert!(sign.points().any(|p| negative.get_pixel(p).is_some()));
+    /// assert!(sign.points().all(|p| positive.get_pixel(p).is_none()));
+    /// ```
+    pub fn draw_signed<D>(&self, value: i32, pos: Point, target: &mut D) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut buf = [0; DECIMAL_BUF_LEN];
+        let text = format_decimal(value.unsigned_abs(), false, 0, &mut buf);
+        let glyphs = iter::once(Some(Glyph::Sign(value < 0))).chain(self.glyphs(text));
+        self.draw_glyph_line(glyphs, pos, Baseline::Top, &[], None, target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    /// 温度の単位("°C"か"°F")を描画します。
+    /// * `unit`   - 描画する単位
+    /// * `pos`    - 左上隅の位置
+    /// * `target` - 描画対象
+    ///
+    /// 上端に小さな丸を描く度記号に続けて、7セグメントの"C"か"F"を描画します。
+    /// 戻り値は、draw_stringと同じく、描画後のカーソル位置です。draw_i32などの戻り値を
+    /// posに渡すと、数値の後ろに続けて描画できます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::{Font7Seg, TempUnit};
+    /// let font = Font7Seg::new(Size::new(8, 16), BinaryColor::On);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = font.draw_i32(-12, 0, Point::zero(), &mut display).unwrap();
+    /// let next = font.draw_temp_unit(TempUnit::Celsius, next, &mut display).unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let expected_next = Text::with_baseline("-12°C", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// ```
+    pub fn draw_temp_unit<D>(
+        &self,
+        unit: TempUnit,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let text = match unit {
+            TempUnit::Celsius => "°C",
+            TempUnit::Fahrenheit => "°F",
+        };
+        self.draw_string(text, pos, Baseline::Top, target)
+    }
+
+    /// 文字列を、上から下へ縦に並べて描画します。
+    /// * `text`   - 描画する文字列
+    /// * `pos`    - 最初の文字の左上隅の位置
+    /// * `target` - 描画対象
+    ///
+    /// 一文字ごとに、数字の高さ(と文字間の間隔)だけ下へ進みます。小数点も、
+    /// 一文字分の高さを使います。with_rotationで90度か270度に回転する場合は、
+    /// 回転後の各文字の高さだけ進みます。戻り値は、最後の文字の下の位置です。
+    pub fn draw_string_vertical<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let colors = [self.text_color; 7];
+        let mut cur_pos = pos;
+        let mut prev_area = Rectangle::new(cur_pos, Size::zero());
+        let mut started = false;
+        for glyph in self.glyphs(text).flatten() {
+            if glyph == Glyph::AttachedPoint {
+                let offset = Point::new(self.attached_point_offset() as i32, 0);
+                let point_pos = prev_area.top_left + offset;
+                let mut target = Rotated::new(target, self.rotation, prev_area);
+                self.draw_cell(point_pos, Some(glyph), false, &colors, None, &mut target)?;
+                continue;
+            }
+            if started {
+                if !self.fits_coordinates(cur_pos, self.char_spacing) {
+                    break;
+                }
+                cur_pos += Size::new(0, self.char_spacing);
+            }
+            started = true;
+            let area = self.cell_area(cur_pos, glyph);
+            let mut target = Rotated::new(target, self.rotation, area);
+            self.draw_cell(cur_pos, Some(glyph), true, &colors, None, &mut target)?;
+            prev_area = area;
+            cur_pos += Size::new(0, transform::rotate_rect(self.rotation, area).size.height);
+        }
+        Ok(cur_pos)
+    }
+
+    /// 文字列を、右端をそろえて描画します。
+    /// * `text`      - 描画する文字列
+    /// * `right_pos` - 文字列の右端の位置。right_pos.xの列が、最後に描画する列になります。
+    /// * `baseline`  - 垂直方向の原点の位置
+    /// * `target`    - 描画対象
+    ///
+    /// 戻り値は、draw_stringと同じく、描画後のカーソル位置です。
+    pub fn draw_string_right_aligned<D>(
+        &self,
+        text: &str,
+        right_pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let font = self.with_origin(Origin::TopLeft);
+        let width = font
+            .drawn_metrics(text, Point::zero(), baseline)
+            .next_position
+            .x;
+        let pos = right_pos - Point::new(width - 1, 0);
+        font.draw_string(text, pos, baseline, target)
+    }
+
+    /// 文字列を、最初の小数点の左端がdecimal_xの列に来るように描画します。
+    /// * `text`      - 描画する文字列
+    /// * `decimal_x` - 小数点の左端の列
+    /// * `pos`       - 垂直方向の位置。pos.yが数字の上端(Baseline::Top)になります。
+    ///   pos.xは使いません。
+    /// * `target`    - 描画対象
+    ///
+    /// 表の各行を、同じdecimal_xで描画すると、"12.5"、"3.25"、"104.0"の小数点が
+    /// 縦に揃います。小数点が無い場合は、末尾に小数点が続くものとして揃えるので、
+    /// "104"の整数部も、他の行の整数部と揃います。with_attached_pointを指定した場合は、
+    /// 直前の数字に重ねた小数点の位置で揃えます。
+    ///
+    /// 戻り値は、draw_stringと同じく、描画後のカーソル位置です。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(8, 16), BinaryColor::On);
+    /// let mut aligned: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// // 数字の幅は8なので、小数点の左端を24に揃えると、整数部は24より左に並ぶ
+    /// for (text, x, y) in [("1.5", 16, 0), ("10.25", 8, 16), ("104", 0, 32)] {
+    ///     font.draw_string_decimal_aligned(text, 24, Point::new(0, y), &mut aligned)
+    ///         .unwrap();
+    ///     Text::with_baseline(text, Point::new(x, y), font, Baseline::Top)
+    ///         .draw(&mut expected)
+    ///         .unwrap();
+    /// }
+    /// aligned.assert_eq(&expected);
+    /// ```
+    pub fn draw_string_decimal_aligned<D>(
+        &self,
+        text: &str,
+        decimal_x: i32,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let point_x = self.decimal_point_x(text);
+        let start = Point::new(decimal_x - point_x, pos.y);
+        self.with_origin(Origin::TopLeft)
+            .draw_string(text, start, Baseline::Top, target)
+    }
+
+    /// 文字列を原点から描画したときの、最初の小数点の左端の位置。
+    /// 小数点が無い場合は、末尾に小数点を続けたときの位置。
+    fn decimal_point_x(&self, text: &str) -> i32 {
+        let mut last = None;
+        for (c, cell) in self.measure_chars(text, Point::zero()) {
+            if to_halfwidth(c) == '.' {
+                return cell.top_left.x;
+            }
+            last = Some((c, cell));
+        }
+        match last {
+            None => 0,
+            Some((c, cell)) if self.attached_point && to_halfwidth(c).is_ascii_hexdigit() => {
+                cell.top_left.x + self.attached_point_offset() as i32
+            }
+            Some((_, cell)) => {
+                let right = offset_x(cell.top_left, cell.size.width);
+                offset_x(right, self.char_spacing).x
+            }
+        }
+    }
+
+    /// 文字列を、boundsの範囲の上下左右の中央に描画します。
+    /// * `text`   - 描画する文字列
+    /// * `bounds` - 描画する範囲
+    /// * `target` - 描画対象
+    ///
+    /// 位置は、measure_stringのbounding_boxが中央に来るように決めます。
+    /// boundsからはみ出す部分は描画しません。
+    pub fn draw_centered<D>(
+        &self,
+        text: &str,
+        bounds: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let text_box = self
+            .drawn_metrics(text, Point::zero(), Baseline::Top)
+            .bounding_box;
+        let offset = Point::new(
+            centered_offset(bounds.size.width, text_box.size.width),
+            centered_offset(bounds.size.height, text_box.size.height),
+        );
+        let pos = bounds.top_left + offset - text_box.top_left;
+        self.draw_string(text, pos, Baseline::Top, &mut target.clipped(&bounds))?;
+        Ok(())
+    }
+
+    /// 文字列を、clipの左上隅からoffsetピクセルだけ左へずらして描画します。
+    /// * `text`   - 描画する文字列
+    /// * `clip`   - 描画する範囲
+    /// * `offset` - 左へずらすピクセル数
+    /// * `target` - 描画対象
+    ///
+    /// clipからはみ出す部分は描画しません。コマごとにoffsetを増やして描画すると、
+    /// 細い表示欄の中を、長い文字列が流れる表示になります。
+    ///
+    /// <戻り値>
+    /// 正常の場合、文字列全体の幅(measure_stringのbounding_boxの幅)を返します。
+    /// offsetを、この幅とclipの幅から折り返すのに使えます。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::{mock_display::MockDisplay, primitives::Rectangle};
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let clip = Rectangle::new(Point::new(10, 5), Size::new(20, 20));
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let width = font.draw_string_scrolled("12345", clip, 15, &mut display).unwrap();
+    /// assert_eq!(width, 50);
+    ///
+    /// // "12345"を15ピクセル左から描画して、clipの中だけを残したものと同じ
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("12345", Point::new(-5, 5), font, Baseline::Top)
+    ///     .draw(&mut expected.clipped(&clip))
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// ```
+    pub fn draw_string_scrolled<D>(
+        &self,
+        text: &str,
+        clip: Rectangle,
+        offset: i32,
+        target: &mut D,
+    ) -> Result<u32, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let font = self.with_origin(Origin::TopLeft);
+        let pos = clip.top_left - Point::new(offset, 0);
+        font.draw_string(text, pos, Baseline::Top, &mut target.clipped(&clip))?;
+        Ok(font
+            .drawn_metrics(text, Point::zero(), Baseline::Top)
+            .bounding_box
+            .size
+            .width)
+    }
+
+    /// 0〜9の数字一文字で、カーソルの進む幅の最大値を返します。
+    ///
+    /// 値が変わる表示の場所を確保するときに使えます。現在の設定(with_proportionalや
+    /// with_segment_patternsなど)を反映します。文字間の間隔と、小数点は含みません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_proportional(true);
+    /// assert_eq!(font.max_digit_advance(), 10);
+    /// ```
+    pub fn max_digit_advance(&self) -> u32 {
+        self.seg_pats[..10]
+            .iter()
+            .map(|&seg_pat| self.glyph_width(Glyph::Segments(seg_pat)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 数字digits桁と小数点points個を並べた場合の、カーソルの進む幅を返します。
+    ///
+    /// 文字列を作らずに、measure_stringのnext_positionと同じ幅を求めます。文字間の間隔も
+    /// 含みます。with_attached_pointを指定した場合、小数点は数字の後ろに付くものとして、
+    /// 幅に含めません。数字は、すべて通常の幅のセルとして数えます(with_proportionalの
+    /// 幅の狭い"1"は考えません)。傾けた場合にはみ出す部分は含みません。
+    pub fn width_for(&self, digits: u32, points: u32) -> u32 {
+        let point = if self.attached_point {
+            Glyph::AttachedPoint
+        } else {
+            Glyph::Point
+        };
+        let glyphs = iter::repeat(Glyph::Segments(SEG_PATS[8]))
+            .take(digits as usize)
+            .chain(iter::repeat(point).take(points as usize));
+        let (advance, _) = self.line_width(glyphs);
+        self.next_position(Point::zero(), advance).x as u32
+    }
+
+    /// 文字列の各文字を、posを左上隅として描画したときの、セルの範囲を返します。
+    ///
+    /// 戻り値は、描画する文字と、そのセルの範囲の組を、文字列の順に返すイテレータです。
+    /// 未対応の文字は含みません。セルの範囲は、draw_stringでBaseline::Topを指定した場合と
+    /// 同じ位置で、文字間の間隔は含みません。with_attached_pointの小数点は、直前の数字の
+    /// セルの右下に重なる範囲になります。タップされた桁の判定などに使えます。
+    /// 回転、左右反転、傾きは考えません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, renderer::TextRenderer}, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let mut cells = font.measure_chars("1x2.", Point::zero());
+    /// let (c, cell) = cells.next().unwrap();
+    /// assert_eq!((c, cell.top_left, cell.size), ('1', Point::zero(), Size::new(10, 20)));
+    /// let (c, cell) = cells.next().unwrap();
+    /// assert_eq!((c, cell.top_left), ('2', Point::new(10, 0)));
+    /// let (c, cell) = cells.next().unwrap();
+    /// assert_eq!((c, cell.top_left), ('.', Point::new(20, 0)));
+    /// let next = font.measure_string("1x2.", Point::zero(), Baseline::Top).next_position;
+    /// assert_eq!(cell.top_left.x + cell.size.width as i32, next.x);
+    /// assert!(cells.next().is_none());
+    /// ```
+    pub fn measure_chars<'a>(
+        &'a self,
+        text: &'a str,
+        pos: Point,
+    ) -> impl Iterator<Item = (char, Rectangle)> + 'a {
+        let mut x = pos.x;
+        let mut prev_x = pos.x;
+        let mut started = false;
+        let mut full = 0;
+        text.chars()
+            .zip(self.glyphs(text))
+            .filter_map(move |(c, glyph)| {
+                let glyph = glyph?;
+                let height = self.size.height;
+                if glyph == Glyph::AttachedPoint {
+                    let offset = self.attached_point_offset();
+                    let left = Point::new(prev_x + offset as i32, pos.y);
+                    let size = Size::new(self.size.width.saturating_sub(offset), height);
+                    return Some((c, Rectangle::new(left, size)));
+                }
+                // 文字間の間隔は、2文字目以降の前に入れる。
+                if started {
+                    x = offset_x(Point::new(x, 0), self.char_spacing).x;
+                }
+                started = true;
+                let width = self.glyph_width(glyph);
+                let cell = Rectangle::new(Point::new(x, pos.y), Size::new(width, height));
+                prev_x = x;
+                x += width as i32;
+                if self.is_full_width(glyph) {
+                    full += 1;
+                    x += (self.fraction_width(full) - self.fraction_width(full - 1)) as i32;
+                }
+                Some((c, cell))
+            })
+    }
+
+    /// 各セグメントの形を求めておいた、PreparedFont7Segに変換します。
+    ///
+    /// 同じフォントで繰り返し描画する場合に、描画のたびの計算を省けます。
+    /// 描画結果は変わりません。
+    pub fn prepared(self) -> PreparedFont7Seg<C> {
+        PreparedFont7Seg::new(self)
+    }
+
+    /// 0〜9の数字を一文字、posを左上隅とするセルに描画します。
+    ///
+    /// 戻り値は、描画したセルの範囲(マージンを含む)です。タッチパネルの当たり判定などに
+    /// 使えます。digitが9より大きい場合は、何も描画せず、大きさ0の範囲を返します。
+    pub fn draw_digit<D>(
+        &self,
+        digit: u8,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Rectangle, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if digit > 9 {
+            return Ok(Rectangle::zero());
+        }
+        let glyph = Glyph::Segments(self.seg_pats[digit as usize]);
+        let colors = [self.text_color; 7];
+        let area = self.cell_area(pos, glyph);
+        let mut target = Rotated::new(target, self.rotation, area);
+        self.draw_cell(pos, Some(glyph), true, &colors, None, &mut target)?;
+        Ok(transform::rotate_rect(self.rotation, area))
+    }
+
+    /// posを左上隅とする一文字分のセルを、背景色で塗りつぶします。
+    ///
+    /// draw_stringが一文字ごとに塗る背景と、同じ範囲を塗ります(傾けた場合は、傾けた
+    /// 範囲を塗ります)。多桁の表示の、一桁だけを消すのに使えます。背景色を指定して
+    /// いない場合は、何もしません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::renderer::CharacterStyle, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// font.set_background_color(Some(BinaryColor::Off));
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.clear_cell(Point::new(10, 0), &mut display).unwrap();
+    /// let area = display.affected_area();
+    /// assert_eq!((area.top_left, area.size), (Point::new(10, 0), Size::new(10, 20)));
+    /// ```
+    pub fn clear_cell<D>(&self, pos: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let colors = [self.text_color; 7];
+        let area = self.cell_area(pos, Glyph::Segments(0));
+        let mut target = Rotated::new(target, self.rotation, area);
+        self.draw_cell(pos, None, true, &colors, None, &mut target)?;
+        Ok(())
+    }
+
+    /// posを左上隅として、一文字分のセルをcount個並べた範囲を、背景色で塗りつぶします。
+    ///
+    /// 空白をcount個並べた文字列をdraw_stringした場合と、同じ範囲を塗ります(文字間の
+    /// 間隔も含みます)。多桁の表示の場所を、起動時にまとめて消すのに使えます。背景色を
+    /// 指定していない場合は、何も描画しません。
+    ///
+    /// <戻り値>
+    /// 描画後のカーソル位置を返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, text::renderer::CharacterStyle, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut font = Font7Seg::new(Size::new(10, 20), BinaryColor::On)
+    ///     .with_char_spacing(2)
+    ///     .with_slant(10.0);
+    /// font.set_background_color(Some(BinaryColor::Off));
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let next = font.draw_blank_field(4, Point::new(1, 1), &mut display).unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let expected_next = Text::with_baseline("    ", Point::new(1, 1), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// ```
+    pub fn draw_blank_field<D>(
+        &self,
+        count: u32,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyphs = iter::repeat(Glyph::Segments(0)).take(count as usize);
+        let (advance, width) = self.line_width(glyphs);
+        let area = Rectangle::new(pos, Size::new(width, self.size.height));
+        let mut target = Rotated::new(target, self.rotation, area);
+        self.fill_gap(pos, advance, &mut target)?;
+        Ok(self.next_position(pos, advance))
+    }
+
+    /// 0〜9の数字を一文字描画した場合に、点灯するピクセルの数の概算値を返します。
+    ///
+    /// 実際には描画せず、各セグメントの形の面積から求めます。誤差は数ピクセル程度です。
+    /// アンチエイリアスによる中間色のピクセルと、セグメント同士の重なりは考慮しません。
+    /// digitが9より大きい場合は、0を返します。
+    pub fn count_lit_pixels(&self, digit: u8) -> u32 {
+        if digit > 9 {
+            return 0;
+        }
+        let seg_pat = self.seg_pats[digit as usize];
+        let polygons = self.segment_polygons(self.inner_area(self.size).size);
+        let area: f32 = polygons
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| seg_pat & (1 << i) != 0)
+            .map(|(_, points)| self.polygon_area(points))
+            .sum();
+        area.round() as u32
+    }
+
+    /// 小数点を描画した場合に、点灯するピクセルの数の概算値を返します。
+    ///
+    /// count_lit_pixelsと同じく、実際には描画せずに求めます。
+    pub fn count_lit_pixels_point(&self) -> u32 {
+        let inner_width = self.inner_area(self.size).size.width;
+        let diameter = scale_ceil(inner_width, self.point_width_rate) as f32;
+        match self.point_shape {
+            PointShape::Circle => {
+                (core::f32::consts::FRAC_PI_4 * diameter * diameter).round() as u32
+            }
+            PointShape::Square => (diameter * diameter) as u32,
+        }
+    }
+
+    /// A〜Gの各セグメントの輪郭の頂点を、セルの左上隅を(0.0, 0.0)、右下隅を(1.0, 1.0)と
+    /// する座標で返します。
+    ///
+    /// 戻り値の並びは、segment_outlinesと同じです。マージンとセグメントの太さの比率、
+    /// SEGMENT_TAPER_RATEなどの形の比率から、丸めずに求めた輪郭で、頂点はピクセルの中心
+    /// ではなく、セグメントの外周の位置です。セグメントの太さは数字の幅から決まるので、
+    /// 値は幅と高さの比によって変わりますが、同じ比の大きさであれば、大きさによらず
+    /// 同じ値になります。ピクセル単位の設定である、セグメントの隙間は含みません。
+    ///
+    /// segment_outlinesは、同じ比率から、ピクセル単位に丸めて求めた頂点です。
+    /// 大きなセルでは、この座標にセルの大きさを掛けた位置と、1ピクセル以内で一致します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let small = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// let large = Font7Seg::new(Size::new(2000, 4000), BinaryColor::On);
+    /// let outlines = large.normalized_segment_outlines();
+    /// assert_eq!(small.normalized_segment_outlines(), outlines);
+    /// // Aの上端は、上のマージン(高さの0.05倍)の位置
+    /// assert!(outlines[0].iter().all(|&(_, y)| y >= 0.05));
+    ///
+    /// let pixels = large.segment_outlines();
+    /// for (normalized, pixel) in outlines.iter().zip(pixels.iter()) {
+    ///     for (&(x, y), p) in normalized.iter().zip(pixel.iter()) {
+    ///         assert!((x * 2000.0 - p.x as f32).abs() <= 1.0);
+    ///         assert!((y * 4000.0 - p.y as f32).abs() <= 1.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn normalized_segment_outlines(&self) -> [[(f32, f32); 6]; 7] {
+        // 横方向の長さは数字の幅、縦方向の長さは数字の高さを1.0として計算する。
+        let aspect = self.size.width as f32 / self.size.height.max(1) as f32;
+        let (left, top) = (self.left_margin_rate, self.top_margin_rate);
+        let inner_width = 1.0 - left * 2.0;
+        let inner_height = 1.0 - top * 2.0;
+        // セグメントの太さと、尖った端の長さ(横方向と縦方向)
+        let line_x = inner_width * self.line_width_rate;
+        let line_y = line_x * aspect;
+        let taper_x = line_x * SEGMENT_TAPER_RATE;
+        let taper_y = line_y * SEGMENT_TAPER_RATE;
+        // 横のセグメント。top_yは、セグメントの上端の位置。
+        let trim = taper_x.min(inner_width / 2.0);
+        let hori = |top_y: f32| {
+            let (x0, x1) = (trim, inner_width - trim);
+            let base = taper_x.min((x1 - x0) / 2.0);
+            let center = top_y + line_y / 2.0;
+            let bottom = top_y + line_y;
+            [
+                (x0, center),
+                (x0 + base, top_y),
+                (x1 - base, top_y),
+                (x1, center),
+                (x1 - base, bottom),
+                (x0 + base, bottom),
+            ]
+        };
+        // 縦のセグメント。上半分(B,F)と、その上下の鏡像の位置の下半分(C,E)
+        let half = inner_height / 2.0;
+        let seg_top = half * SEGMENT_OFFSET_RATE + line_y / 2.0;
+        let seg_bottom = seg_top + half * SEGMENT_LENGTH_RATE;
+        let base = taper_y.min((seg_bottom - seg_top) / 2.0);
+        let vert = |right: bool, lower: bool| {
+            let x0 = if right { inner_width - line_x } else { 0.0 };
+            let (y0, y1) = if lower {
+                (inner_height - seg_bottom, inner_height - seg_top)
+            } else {
+                (seg_top, seg_bottom)
+            };
+            [
+                (x0 + line_x / 2.0, y0),
+                (x0 + line_x, y0 + base),
+                (x0 + line_x, y1 - base),
+                (x0 + line_x / 2.0, y1),
+                (x0, y1 - base),
+                (x0, y0 + base),
+            ]
+        };
+        let polygons = [
+            hori(0.0),
+            vert(true, false),
+            vert(true, true),
+            hori(inner_height - line_y),
+            vert(false, true),
+            vert(false, false),
+            hori((inner_height - line_y) / 2.0),
+        ];
+        polygons.map(|points| points.map(|(x, y)| (x + left, y + top)))
+    }
+
+    /// A〜Gの各セグメントの輪郭の頂点を、セルの左上隅を原点とする座標で返します。
+    ///
+    /// 戻り値の[0]がA、[1]がB、…、[6]がGで、各頂点はピクセルの中心の座標です。
+    /// セグメントの太さやマージンは、セルの大きさに応じてピクセル単位に丸めるので、
+    /// 大きさに依存しない比率ではなく、このフォントの設定での実際の座標を返します。
+    /// 大きさに依存しない座標は、normalized_segment_outlinesで求められます。
+    /// SegmentShape::Pointedでは、この六角形を塗ります。Flatでは[5]と[2]を対角とする
+    /// 長方形を、Roundedではその両端を半円で丸めた形を塗ります。
+    /// 傾きや左右反転は、含みません。
+    ///
+    /// 頂点は整数で計算するので、書き出し用の大きなセルでも、丸め誤差でずれません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(2000, 4000), BinaryColor::On);
+    /// let outlines = font.segment_outlines();
+    /// let rows = |i: usize| {
+    ///     let ys = outlines[i].iter().map(|p| p.y);
+    ///     (ys.clone().min().unwrap(), ys.max().unwrap())
+    /// };
+    /// let cols = |i: usize| {
+    ///     let xs = outlines[i].iter().map(|p| p.x);
+    ///     (xs.clone().min().unwrap(), xs.max().unwrap())
+    /// };
+    /// // 太さは、描画領域の幅1800の0.2倍
+    /// let (a_top, a_bottom) = rows(0);
+    /// assert_eq!(a_bottom - a_top + 1, 360);
+    /// let (b_left, b_right) = cols(1);
+    /// assert_eq!(b_right - b_left + 1, 360);
+    /// // Gは、AとDの中央に来る
+    /// let (d_top, d_bottom) = rows(3);
+    /// let (g_top, g_bottom) = rows(6);
+    /// assert_eq!(d_bottom - d_top, a_bottom - a_top);
+    /// assert!(((g_top + g_bottom) - (a_top + d_bottom)).abs() <= 1);
+    /// ```
+    pub fn segment_outlines(&self) -> [[Point; 6]; 7] {
+        let inner = self.inner_area(self.size);
+        let mut polygons = self.segment_polygons(inner.size);
+        for points in polygons.iter_mut() {
+            for point in points.iter_mut() {
+                *point += inner.top_left;
+            }
+        }
+        polygons
+    }
+
+    /// 指定したセグメントだけを点灯させた一文字を、posを左上隅とするセルに描画します。
+    ///
+    /// 独自の字形や、セグメントを一本ずつ点灯させるアニメーションなどに使えます。
+    /// 背景色や消灯セグメントの色の設定は、draw_stringと同様に反映されます。
+    pub fn draw_segments<D>(
+        &self,
+        segs: &[Segment],
+        pos: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyph = Glyph::Segments(segs.iter().fold(0, |pat, seg| pat | seg.bit()));
+        let colors = [self.text_color; 7];
+        let mut target = Rotated::new(target, self.rotation, self.cell_area(pos, glyph));
+        self.draw_cell(pos, Some(glyph), true, &colors, None, &mut target)?;
+        Ok(())
+    }
+
+    /// 点灯するセグメントの数で、0〜7の段階を表す一文字を、posを左上隅とするセルに描画します。
+    ///
+    /// levelの数だけ、D、G、A、E、C、F、Bの順にセグメントを点灯します。下から上へ
+    /// 横のセグメントが、続いて下から上へ縦のセグメントが点灯します。
+    /// - 0 : 点灯なし
+    /// - 1 : D
+    /// - 2 : D, G
+    /// - 3 : D, G, A
+    /// - 4 : D, G, A, E
+    /// - 5 : D, G, A, E, C
+    /// - 6 : D, G, A, E, C, F
+    /// - 7 : すべて
+    ///
+    /// 7より大きいlevelは、7とみなします。レベルメーターなどに使えます。
+    pub fn draw_level<D>(&self, level: u8, pos: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        const ORDER: [Segment; 7] = [
+            Segment::D,
+            Segment::G,
+            Segment::A,
+            Segment::E,
+            Segment::C,
+            Segment::F,
+            Segment::B,
+        ];
+        let level = (level as usize).min(ORDER.len());
+        self.draw_segments(&ORDER[..level], pos, target)
+    }
+
+    /// 文字列を、posを左上隅として、一文字ずつ色を変えて描画します。
+    ///
+    /// 描画するi番目の文字を、colors[i]の色で描画します。colorsが文字数より短い場合は、
+    /// 先頭から繰り返して使います。空の場合は、文字色で描画します。未対応の文字は、
+    /// draw_stringと同じく無視し、色も使いません。特定の桁の強調などに使えます。
+    ///
+    /// <戻り値>
+    /// 正常の場合、描画後のカーソル位置を返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::Rgb565};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb565::WHITE);
+    /// let mut display: MockDisplay<Rgb565> = MockDisplay::new();
+    /// font.draw_string_colored("888", &[Rgb565::RED, Rgb565::GREEN], Point::zero(), &mut display)
+    ///     .unwrap();
+    /// let color_at = |x: i32| display.get_pixel(Point::new(x, 10));
+    /// // 横のセグメントGの色を、桁ごとに確かめる
+    /// assert_eq!(color_at(5), Some(Rgb565::RED));
+    /// assert_eq!(color_at(15), Some(Rgb565::GREEN));
+    /// assert_eq!(color_at(25), Some(Rgb565::RED));
+    /// ```
+    pub fn draw_string_colored<D>(
+        &self,
+        text: &str,
+        colors: &[C],
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_glyph_line(self.glyphs(text), pos, Baseline::Top, colors, None, target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    /// 文字列を、enabledで指定した文字だけ描画します。
+    /// * `text`    - 描画する文字列
+    /// * `enabled` - 描画する文字。bit iが1の場合、先頭から数えてi番目(0番目から)の文字を
+    ///   描画します。小数点なども、一文字として数えます。32文字目以降は、常に描画します。
+    /// * `pos`     - 左上隅の位置
+    /// * `target`  - 描画対象
+    ///
+    /// bitが0の文字は、同じ幅だけカーソルを進め、背景だけを塗ります(背景色を指定して
+    /// いなければ何も描画しません)。with_attached_pointで数字に重ねる小数点は、描画しない
+    /// だけで、幅は変わりません。ダイナミック点灯の表示器のように、桁ごとに表示を
+    /// 切り替えるのに使えます。with_blank_leading_zerosと違い、0以外の文字も消せます。
+    ///
+    /// <戻り値>
+    /// 正常の場合、描画後のカーソル位置を返します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, text::renderer::CharacterStyle, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// font.set_background_color(Some(BinaryColor::Off));
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// display.set_allow_overdraw(true);
+    /// // 先頭の2文字(bit0とbit1)を消す
+    /// let next = font
+    ///     .draw_string_masked("12.5", 0b1100, Point::zero(), &mut display)
+    ///     .unwrap();
+    ///
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// expected.set_allow_overdraw(true);
+    /// let expected_next = Text::with_baseline("  .5", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut expected)
+    ///     .unwrap();
+    /// display.assert_eq(&expected);
+    /// assert_eq!(next, expected_next);
+    /// ```
+    pub fn draw_string_masked<D>(
+        &self,
+        text: &str,
+        enabled: u32,
+        pos: Point,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyphs = self.glyphs(text).enumerate().filter_map(move |(i, glyph)| {
+            let shown = i >= 32 || enabled & (1 << i) != 0;
+            match glyph {
+                Some(Glyph::AttachedPoint) if !shown => None,
+                Some(glyph) if !shown => Some(Some(Glyph::Blank {
+                    width: self.glyph_width(glyph),
+                    shift: self.narrow_shift(glyph),
+                })),
+                glyph => Some(glyph),
+            }
+        });
+        self.draw_glyph_line(glyphs, pos, Baseline::Top, &[], None, target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    /// セグメントのパターンの並びを、posを左上隅として、一桁ずつ並べて描画します。
+    ///
+    /// 各バイトのbit0〜bit6がセグメントA〜G、bit7が小数点です。小数点は、
+    /// with_attached_pointを指定した場合と同じく、その桁の右下隅に重ねて描画します。
+    /// 表示器の駆動データを、文字に変換せずにそのまま表示するのに使えます。
+    ///
+    /// <戻り値>
+    /// 正常の場合、描画後のカーソル位置を返します。
+    pub fn draw_masks<D>(&self, masks: &[u8], pos: Point, target: &mut D) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyphs = masks.iter().flat_map(|&mask| {
+            let point = (mask & 0x80 != 0).then_some(Some(Glyph::AttachedPoint));
+            iter::once(Some(Glyph::Segments(mask & 0x7f))).chain(point)
+        });
+        self.draw_glyph_line(glyphs, pos, Baseline::Top, &[], None, target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    /// 文字列をdraw_stringと同じく描画し、描画した各セルのセグメントのパターンを、
+    /// outに順に書き込みます。
+    /// * `text`   - 描画する文字列
+    /// * `pos`    - 左上隅の位置
+    /// * `target` - 描画対象
+    /// * `out`    - パターンを書き込むバッファ
+    ///
+    /// パターンは、draw_masksと同じく、bit0〜bit6がセグメントA〜G、bit7が小数点です。
+    /// 描画したセル一つにつき1バイトを書き込みます。with_attached_pointで数字に重ねた
+    /// 小数点は、その数字のbit7になります。独立したセルの小数点とカンマは0x80、度記号は
+    /// A、B、F、G、プラス記号はGのパターンです。7セグメントで表せないコロンと、
+    /// 未対応の文字のセルは、0(消灯)です。画面と同じ内容を、外付けの7セグメント表示器に
+    /// 送るのに使えます。
+    ///
+    /// <戻り値>
+    /// 正常の場合、outに書き込んだパターンの数を返します。outに入りきらない分は、
+    /// 書き込みません。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On).with_attached_point(true);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// let mut masks = [0; 8];
+    /// let count = font
+    ///     .draw_string_with_masks("-1.5", Point::zero(), &mut display, &mut masks)
+    ///     .unwrap();
+    /// assert_eq!(&masks[..count], &[0b0100_0000, 0b1000_0110, 0b0110_1101]);
+    ///
+    /// // 書き込んだパターンを描画すると、同じ表示になる
+    /// let mut expected: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// font.draw_masks(&masks[..count], Point::zero(), &mut expected).unwrap();
+    /// display.assert_eq(&expected);
+    /// ```
+    pub fn draw_string_with_masks<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        target: &mut D,
+        out: &mut [u8],
+    ) -> Result<usize, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_text(text, pos, Baseline::Top, None, target)?;
+        let mut count: usize = 0;
+        for glyph in self.glyphs(text) {
+            if glyph == Some(Glyph::AttachedPoint) {
+                // 直前のセルのパターンに、小数点を加える。
+                if let Some(mask) = count.checked_sub(1).and_then(|i| out.get_mut(i)) {
+                    *mask |= 0x80;
+                }
+                continue;
+            }
+            if let Some(mask) = out.get_mut(count) {
+                *mask = glyph.map_or(0, Glyph::mask);
+            }
+            count += 1;
+        }
+        Ok(count.min(out.len()))
+    }
+
+    /// 全セグメントと小数点を点灯させた一文字("8."の形)を、posを左上隅とするセルに
+    /// 描画します。
+    ///
+    /// 表示器の立ち上げ時に、欠けたピクセルが無いかを確かめる、自己診断用の字形です。
+    /// 小数点は、with_attached_pointを指定した場合と同じく、同じセルの右下に描画します。
+    /// 色は、文字色です。
+    pub fn draw_test_pattern<D>(&self, pos: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_masks(&[0xff], pos, target)?;
+        Ok(())
+    }
+
+    /// 数字を一文字、セグメントごとに色を変えて描画します。
+    /// * `num`    - 描画する数字一桁(16進)。16以上の場合、16進の一桁目のみ有効です。
+    /// * `colors` - 各セグメントの色。colors[0]がA、colors[1]がB、…、colors[6]がGです。
+    /// * `area`   - 描画対象。原点を左上隅とするセルに描画します。
+    ///
+    /// 配線の確認など、セグメントの並びを目で確かめるのに使えます。
+    /// 各セグメントの位置は、Segmentの説明の図のとおりです。
+    pub fn draw_number_with_colors<D>(
+        &self,
+        num: u32,
+        colors: &[C; 7],
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let glyph = Glyph::Segments(self.seg_pats[(num % 16) as usize]);
+        let cell_area = self.cell_area(Point::zero(), glyph);
+        let mut area = Rotated::new(area, self.rotation, cell_area);
+        self.draw_cell(Point::zero(), Some(glyph), true, colors, None, &mut area)?;
+        Ok(())
+    }
+
+    /// 縦のセグメントを、areaの上下を少し短くした範囲に描画する。
+    /// flip_x、flip_yを指定すると、左右・上下を反転した形で描画する。
+    /// 対になるセグメント(BとF、BとCなど)を、互いの鏡像にするために使う。
+    fn draw_segment_vert<D>(
+        &self,
+        color: C,
+        flip_x: bool,
+        flip_y: bool,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let points = self.vert_segment_points(area.bounding_box(), flip_x, flip_y);
+        self.draw_polygon(&points, self.segment_style(color), area)
+    }
+
+    /// 縦のセグメントの頂点を求める。引数は、draw_segment_vertと同じ。
+    fn vert_segment_points(&self, area: Rectangle, flip_x: bool, flip_y: bool) -> [Point; 6] {
+        // 長さを少し短くする。
+        let Size { width, height } = area.size;
+        // 大きなセルでも丸め誤差が出ないよう、整数で計算する。
+        // 上端は 高さ × SEGMENT_OFFSET_RATE + 幅 / 2、長さは 高さ × SEGMENT_LENGTH_RATE
+        // (いずれも切り捨て)
+        let gap = self.segment_gap as i32;
+        let (num, den) = VERT_OFFSET_RATE;
+        let area_top = ((height * num * 2 + width * den) / (den * 2)) as i32 + gap;
+        let area_height = (height * VERT_LENGTH_RATE.0 / VERT_LENGTH_RATE.1) as i32 - gap * 2;
+        let area_top = if flip_y {
+            height as i32 - area_top - area_height
+        } else {
+            area_top
+        };
+        let area_top_left = Point::new(0, area_top);
+        let area_size = Size::new(width, area_height.max(0) as u32);
+        let area = sub_area(&area, Rectangle::new(area_top_left, area_size));
+
+        // 各頂点は、ピクセルの中心に置く。
+        let Size { width, height } = area.size;
+        let right = width as i32 - 1;
+        let bottom = height as i32 - 1;
+        let w_center: i32 = (right + flip_x as i32) / 2;
+        let v_base_top: i32 = (width * TAPER_RATE.0 / TAPER_RATE.1) as i32;
+        let v_base_bottom: i32 = bottom - v_base_top;
+        [
+            Point::new(w_center, 0),
+            Point::new(right, v_base_top),
+            Point::new(right, v_base_bottom),
+            Point::new(w_center, bottom),
+            Point::new(0, v_base_bottom),
+            Point::new(0, v_base_top),
+        ]
+        .map(|p| p + area.top_left)
+    }
+
+    /// 横のセグメントの頂点を、areaの左右を少し短くした範囲に求める。
+    /// flip_yを指定すると、上下を反転した形にする。
+    fn hori_segment_points(&self, area: Rectangle, flip_y: bool) -> [Point; 6] {
+        // 両端を幅の半分(と隙間の分)だけ削る
+        let Size { width, height } = area.size;
+        // 縦長のセルでは、削る幅が領域の幅を超えないようにする。
+        // 高さ × SEGMENT_TAPER_RATE の切り上げを、丸め誤差が出ないよう整数で計算する。
+        let half_width: u32 = (height * TAPER_RATE.0).div_ceil(TAPER_RATE.1) + self.segment_gap;
+        let half_width = half_width.min(width / 2);
+        let new_top_left = Point::new(half_width as i32, 0);
+        let new_size = Size::new(width - half_width * 2, height);
+        let area = sub_area(&area, Rectangle::new(new_top_left, new_size));
+
+        // 各頂点は、ピクセルの中心に置く。
+        let Size { width, height } = area.size;
+        let right = width as i32 - 1;
+        let bottom = height as i32 - 1;
+        let v_center: i32 = (bottom + flip_y as i32) / 2;
+        let h_base_left: i32 = (height * TAPER_RATE.0 / TAPER_RATE.1) as i32;
+        let h_base_right: i32 = right - h_base_left;
+        [
+            Point::new(0, v_center),
+            Point::new(h_base_left, 0),
+            Point::new(h_base_right, 0),
+            Point::new(right, v_center),
+            Point::new(h_base_right, bottom),
+            Point::new(h_base_left, bottom),
+        ]
+        .map(|p| p + area.top_left)
+    }
+
+    /// 頂点pointsのセグメントを、styleで描画する。styleは、segment_style()で作る。
+    fn draw_polygon<D>(
+        &self,
+        points: &[Point; 6],
+        style: PrimitiveStyle<C>,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let (color, stroke) = match (style.fill_color, style.stroke_color) {
+            (Some(color), _) => (color, None),
+            (None, Some(color)) => (color, Some(style.stroke_width)),
+            (None, None) => return Ok(()),
+        };
+        if let Some((diameter, spacing)) = self.dot_segments {
+            return Self::draw_dots(points, diameter, spacing, style, area);
+        }
+        match self.segment_shape {
+            SegmentShape::Pointed => {
+                // 鏡像のセグメントが同じ形になるよう、左右上下に対称な判定で塗る。
+                let aa = match (self.antialiasing, self.mix, self.background_color) {
+                    (true, Some(mix), Some(bg_color)) => Some((bg_color, mix)),
+                    _ => None,
+                };
+                return Self::fill_polygon(points, color, aa, 0, stroke, area);
+            }
+            SegmentShape::Flat => {}
+            SegmentShape::Rounded => {
+                // 両端の、中央の長方形の辺を直径とする円で、端を丸める。
+                // 直径が偶数でも両端が対称になるよう、円は辺から外側へ直径の半分だけ出す。
+                // 半透明に重ねる場合に二度混ぜないよう、円は、長方形と重ならない部分だけを描く。
+                let body = self.segment_body(points);
+                let mut area = Excluding::new(area, body);
+                for (a, b, outward) in [(points[1], points[5], false), (points[2], points[4], true)]
+                {
+                    let d = a - b;
+                    let diameter = (d.x.abs() + d.y.abs()) as u32 + 1;
+                    let ext = (diameter / 2) as i32;
+                    let shift = if outward {
+                        ext - (diameter as i32 - 1)
+                    } else {
+                        -ext
+                    };
+                    let min = a.component_min(b);
+                    let top_left = if d.x == 0 {
+                        Point::new(min.x + shift, min.y)
+                    } else {
+                        Point::new(min.x, min.y + shift)
+                    };
+                    Circle::new(top_left, diameter)
+                        .into_styled(style)
+                        .draw(&mut area)?;
+                }
+            }
+        }
+        match self.segment_body(points) {
+            SegmentBody::Sharp(rect) => rect.into_styled(style).draw(area),
+            SegmentBody::Rounded(rect) => rect.into_styled(style).draw(area),
+        }
+    }
+
+    /// 頂点pointsのセグメントの、中央の長方形。with_corner_radiusを指定した場合は、角を丸める。
+    fn segment_body(&self, points: &[Point; 6]) -> SegmentBody {
+        let rect = Rectangle::with_corners(points[5], points[2]);
+        if self.corner_radius == 0 {
+            SegmentBody::Sharp(rect)
+        } else {
+            let radius = Size::new_equal(self.corner_radius);
+            SegmentBody::Rounded(RoundedRectangle::with_equal_corners(rect, radius))
+        }
+    }
+
+    /// 頂点pointsのセグメントの中心線(両端の頂点を結ぶ線)に沿って、直径diameterの点を
+    /// spacingの隙間で並べて描画する。点の列は、中心線の中央に寄せる。
+    fn draw_dots<D>(
+        points: &[Point; 6],
+        diameter: u32,
+        spacing: u32,
+        style: PrimitiveStyle<C>,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let (start, end) = (points[0], points[3]);
+        let d = end - start;
+        let length = (d.x.abs() + d.y.abs()) as u32 + 1;
+        if length < diameter {
+            return Ok(());
+        }
+        let pitch = diameter + spacing;
+        let count = (length - diameter) / pitch + 1;
+        let used = (count - 1) * pitch + diameter;
+        let offset = ((length - used) / 2 + diameter / 2) as i32;
+        let step = Point::new(d.x.signum(), d.y.signum());
+        for i in 0..count as i32 {
+            let center = start + step * (offset + i * pitch as i32);
+            Circle::with_center(center, diameter)
+                .into_styled(style)
+                .draw(area)?;
+        }
+        Ok(())
+    }
+
+    /// seg_patで指定したセグメントの内側を、セグメントの太さの1/4だけ縮めて、
+    /// colorで描画する。sizeは、polygonsを求めたときの描画領域の大きさ。
+    fn draw_seg_highlight<D>(
+        &self,
+        polygons: &SegmentPolygons,
+        seg_pat: u8,
+        color: C,
+        size: Size,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let line_width = scale_ceil(size.width, self.line_width_rate);
+        let inset = line_width / 4;
+        if inset == 0 || self.segment_style != SegmentStyle::Fill || self.dot_segments.is_some() {
+            return Ok(());
+        }
+        for (i, points) in polygons.iter().enumerate() {
+            if seg_pat & (1 << i) == 0 {
+                continue;
+            }
+            match self.segment_shape {
+                SegmentShape::Pointed => {
+                    Self::fill_polygon(points, color, None, inset, None, area)?
+                }
+                // 丸めた端は縮めず、中央の長方形だけを縮める。
+                SegmentShape::Flat | SegmentShape::Rounded => {
+                    let core =
+                        Rectangle::with_corners(points[5], points[2]).offset(-(inset as i32));
+                    area.fill_solid(&core, color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 凸多角形を塗りつぶす。
+    /// aaに背景色と混色関数を指定すると、辺にかかるピクセルを被覆率に応じて
+    /// 背景色と混ぜながら描画する。insetを指定すると、各辺からその幅だけ内側に縮めた形を
+    /// 塗る。strokeに幅を指定すると、内側は塗らずに、辺からその幅の内側までだけを描画する。
+    /// どれも指定しない場合は、fill_polygon_rowsで、同じ形を長方形に分けて塗る。
+    fn fill_polygon<D>(
+        points: &[Point; 6],
+        color: C,
+        aa: Option<(C, MixFn<C>)>,
+        inset: u32,
+        stroke: Option<u32>,
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if aa.is_none() && inset == 0 && stroke.is_none() {
+            return Self::fill_polygon_rows(points, color, area);
+        }
+        // 1ピクセルを、SUB×SUBの点で標本化する。アンチエイリアスしない場合は、中心の1点。
+        let sub: u32 = if aa.is_some() { 4 } else { 1 };
+        // 頂点はピクセルの中心とし、通常の塗りつぶしと同じ太さになるよう、
+        // 各辺を半ピクセル外側に広げて判定する。
+        let verts = points.map(|p| (p.x as f32 + 0.5, p.y as f32 + 0.5));
+        let mut area2 = 0.0;
+        for i in 0..verts.len() {
+            let (ax, ay) = verts[i];
+            let (bx, by) = verts[(i + 1) % verts.len()];
+            area2 += ax * by - bx * ay;
+        }
+        // 幅1ピクセルのセグメントのように、面積の無い形は、辺から半ピクセル以内を塗る。
+        let orient = if area2 == 0.0 { 1.0 } else { area2.signum() };
+        // 各辺から、内側へ(inset - 0.5)ピクセル以上離れているか
+        let inside = |sx: f32, sy: f32, inset: f32| {
+            let k = inset - 0.5;
+            (0..verts.len()).all(|i| {
+                let (ax, ay) = verts[i];
+                let (bx, by) = verts[(i + 1) % verts.len()];
+                let (ex, ey) = (bx - ax, by - ay);
+                let cross = (ex * (sy - ay) - ey * (sx - ax)) * orient;
+                let limit2 = k * k * (ex * ex + ey * ey);
+                if k <= 0.0 {
+                    cross >= 0.0 || cross * cross <= limit2
+                } else {
+                    cross > 0.0 && cross * cross >= limit2
+                }
+            })
+        };
+        let inset = inset as f32;
+        let on_shape = |sx: f32, sy: f32| match stroke {
+            Some(width) => inside(sx, sy, inset) && !inside(sx, sy, inset + width as f32),
+            None => inside(sx, sy, inset),
+        };
+        let min_x = points.iter().map(|p| p.x).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.x).max().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+        let pixels = (min_y..=max_y)
+            .flat_map(|y| (min_x..=max_x).map(move |x| Point::new(x, y)))
+            .filter_map(|p| {
+                let mut coverage = 0;
+                for j in 0..sub {
+                    for i in 0..sub {
+                        let sx = p.x as f32 + (i as f32 + 0.5) / sub as f32;
+                        let sy = p.y as f32 + (j as f32 + 0.5) / sub as f32;
+                        if on_shape(sx, sy) {
+                            coverage += 1;
+                        }
+                    }
+                }
+                match (coverage, aa) {
+                    (0, _) => None,
+                    (c, Some((bg_color, mix))) if c < sub * sub => Some(Pixel(
+                        p,
+                        mix(color, bg_color, (c * 255 / (sub * sub)) as u8),
+                    )),
+                    _ => Some(Pixel(p, color)),
+                }
+            });
+        area.draw_iter(pixels)
+    }
+
+    /// fill_polygonの、縮めたり混ぜたりしない場合。整数だけで各行の塗る範囲を求め、
+    /// 同じ範囲が続く行をまとめた長方形を、fill_solidで塗る。
+    ///
+    /// 塗るピクセルは、fill_polygonの判定と同じく、中心が、各辺を半ピクセル外側に
+    /// 広げた半平面の内側にあるものとする。頂点も画素も整数の座標なので、辺abからの
+    /// 距離の判定は、外積crossと辺の長さの2乗lenについての、4×cross² ≦ lenになる。
+    fn fill_polygon_rows<D>(points: &[Point; 6], color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let n = points.len();
+        let mut area2: i64 = 0;
+        for i in 0..n {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            area2 += a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64;
+        }
+        let orient: i64 = if area2 < 0 { -1 } else { 1 };
+        let min_x = points.iter().map(|p| p.x).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.x).max().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+        // 行yで塗るxの範囲。辺ごとに、orient×cross ≧ -slackとなるxに絞る。
+        let span = |y: i32| {
+            let (mut lo, mut hi) = (min_x as i64, max_x as i64);
+            for i in 0..n {
+                let (a, b) = (points[i], points[(i + 1) % n]);
+                let (ex, ey) = ((b.x - a.x) as i64, (b.y - a.y) as i64);
+                let slack = isqrt((ex * ex + ey * ey) as u64 / 4) as i64;
+                // orient×cross = k - s×x
+                let k = orient * (ex * (y - a.y) as i64 + ey * a.x as i64);
+                let s = orient * ey;
+                if s > 0 {
+                    hi = hi.min((k + slack).div_euclid(s));
+                } else if s < 0 {
+                    lo = lo.max(-((k + slack).div_euclid(-s)));
+                } else if k < -slack {
+                    return None;
+                }
+            }
+            (lo <= hi).then_some((lo as i32, hi as i32))
+        };
+        let mut run: Option<((i32, i32), i32)> = None;
+        for y in min_y..=max_y + 1 {
+            let row = if y <= max_y { span(y) } else { None };
+            match run {
+                Some((prev, _)) if Some(prev) == row => continue,
+                Some(((lo, hi), top)) => {
+                    let rect = Rectangle::with_corners(Point::new(lo, top), Point::new(hi, y - 1));
+                    area.fill_solid(&rect, color)?;
+                }
+                None => {}
+            }
+            run = row.map(|row| (row, y));
+        }
+        Ok(())
+    }
+
+    /// draw_polygonで描画されるピクセルの数の概算値
+    fn polygon_area(&self, points: &[Point; 6]) -> f32 {
+        let rect = |a: Point, b: Point| {
+            let d = a - b;
+            ((d.x.abs() + 1) * (d.y.abs() + 1)) as f32
+        };
+        match self.segment_shape {
+            SegmentShape::Pointed => {
+                // 頂点はピクセルの中心で、辺を半ピクセル外側に広げて塗るので、
+                // 面積に、周の長さの半分と、角の分を足す。
+                let mut area2 = 0;
+                let mut perimeter = 0.0;
+                for i in 0..points.len() {
+                    let a = points[i];
+                    let b = points[(i + 1) % points.len()];
+                    area2 += a.x * b.y - b.x * a.y;
+                    let d = (b - a).abs();
+                    let (long, short) = (d.x.max(d.y) as f32, d.x.min(d.y) as f32);
+                    // 平方根を使わない、辺の長さの近似
+                    perimeter += long + short * 0.414;
+                }
+                if area2 == 0 {
+                    // fill_polygonは、面積のない多角形を描かない。
+                    return 0.0;
+                }
+                area2.abs() as f32 / 2.0 + perimeter / 2.0 + 1.0
+            }
+            SegmentShape::Flat => rect(points[5], points[2]),
+            SegmentShape::Rounded => {
+                // 両端の半円を合わせて、円一つ分
+                let d = points[1] - points[5];
+                let diameter = (d.x.abs() + d.y.abs() + 1) as f32;
+                rect(points[5], points[2]) + core::f32::consts::FRAC_PI_4 * diameter * diameter
+            }
+        }
+    }
+
+    /// 大きさsizeの描画領域での、A〜Gの各セグメントの頂点を求める。
+    fn segment_polygons(&self, size: Size) -> SegmentPolygons {
+        #[cfg(test)]
+        tests::SEGMENT_POLYGONS_CALLS.with(|calls| calls.set(calls.get() + 1));
+        let Size { width, height } = size;
+        let area = Rectangle::new(Point::zero(), size);
+        let line_width: u32 = scale_ceil(width, self.line_width_rate);
+        let hori = |top: i32, flip_y: bool| {
+            let seg_area = Rectangle::new(Point::new(0, top), Size::new(width, line_width));
+            self.hori_segment_points(sub_area(&area, seg_area), flip_y)
+        };
+        // 左右の縦のセグメント(B,C,E,F)。Fを基準に、右(right)と下(lower)は、
+        // その鏡像として描く。
+        let vert = |right: bool, lower: bool| {
+            let seg_height: u32 = height.div_ceil(2);
+            let seg_left: i32 = if right {
+                width as i32 - line_width as i32
+            } else {
+                0
+            };
+            let seg_top: i32 = if lower {
+                height as i32 - seg_height as i32
+            } else {
+                0
+            };
+            let seg_area = Rectangle::new(
+                Point::new(seg_left, seg_top),
+                Size::new(line_width, seg_height),
+            );
+            self.vert_segment_points(sub_area(&area, seg_area), right, lower)
+        };
+        let mut polygons = [
+            hori(0, false),
+            vert(true, false),
+            vert(true, true),
+            // Aの上下の鏡像
+            hori(height as i32 - line_width as i32, true),
+            vert(false, true),
+            vert(false, false),
+            hori(centered_offset(height, line_width), false),
+        ];
+        self.tilt_polygons(&mut polygons);
+        polygons
+    }
+
+    /// 縦のセグメントを傾け、横のセグメントを、その中心の行の傾きの分だけずらす。
+    fn tilt_polygons(&self, polygons: &mut SegmentPolygons) {
+        let tilt = self.effective_tilt();
+        if tilt == 0.0 {
+            return;
+        }
+        // 傾きは、全体を傾ける場合(CellMap)と同じく、セルの上端・下端から測る。
+        let inner = self.inner_area(self.size);
+        let cell_top = -inner.top_left.y;
+        let cell_bottom = cell_top + self.size.height as i32 - 1;
+        let shift = |y: i32| {
+            let rows = if tilt >= 0.0 {
+                cell_bottom - y
+            } else {
+                y - cell_top
+            };
+            Point::new((tilt.abs() * rows as f32).round() as i32, 0)
+        };
+        let horizontal = Segment::A.bit() | Segment::D.bit() | Segment::G.bit();
+        for (i, points) in polygons.iter_mut().enumerate() {
+            if horizontal & (1 << i) != 0 {
+                let offset = shift(points[0].y);
+                points.iter_mut().for_each(|p| *p += offset);
+            } else {
+                points.iter_mut().for_each(|p| *p += shift(p.y));
+            }
+        }
+    }
+
+    fn draw_seg_point<D>(&self, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 幅を狭くする
+        let Size { width, height } = area.bounding_box().size;
+        let n_width = scale_ceil(width, self.point_width_rate);
+        let n_size = Size::new(n_width, height);
+        let mut area = area.cropped(&Rectangle::new(Point::new(0, 0), n_size));
+
+        // 丸か、丸と同じ範囲の正方形を描画
+        let style = self.segment_style(color);
+        let circle = Self::point_circle(area.bounding_box().size, self.point_position);
+        match self.point_shape {
+            PointShape::Circle => circle.into_styled(style).draw(&mut area)?,
+            PointShape::Square => circle.bounding_box().into_styled(style).draw(&mut area)?,
+        }
+        Ok(())
+    }
+
+    /// 幅を狭くした大きさsizeの領域に、positionの位置で描く小数点の丸。
+    fn point_circle(size: Size, position: PointPosition) -> Circle {
+        let Size { width, height } = size;
+        let diameter = width;
+        // 直径dの円は、中心から(d-1)/2だけ上の行から塗られる
+        let above = (diameter.saturating_sub(1) / 2) as i32;
+        let center_y = match position {
+            PointPosition::Bottom => height as i32 - diameter as i32,
+            PointPosition::Middle => centered_offset(height, diameter) + above,
+            // Bottomの上下の鏡像。下端から空く行数(above)だけ、上端から空ける。
+            PointPosition::Top => above * 2,
+        };
+        let center = Point::new((width / 2) as i32, center_y);
+        Circle::with_center(center, diameter)
+    }
+
+    /// カンマを描画する。小数点の位置によらず、下端の小数点の丸に、
+    /// 丸の右端から左下へ伸びる三角形の尾を付ける。
+    fn draw_seg_comma<D>(&self, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 幅を狭くする
+        let Size { width, height } = area.bounding_box().size;
+        let n_width = scale_ceil(width, self.point_width_rate);
+        let n_size = Size::new(n_width, height);
+        let mut area = area.cropped(&Rectangle::new(Point::new(0, 0), n_size));
+
+        let style = self.segment_style(color);
+        let circle = Self::point_circle(area.bounding_box().size, PointPosition::Bottom);
+        circle.into_styled(style).draw(&mut area)?;
+        // 尾は、丸の中心と右端の中央を結ぶ辺から、丸の直径だけ下の左端へ伸ばす。
+        let center = circle.center();
+        let right = circle.top_left.x + circle.diameter as i32 - 1;
+        let bottom = circle.top_left.y + circle.diameter as i32 - 1;
+        let tip = Point::new(circle.top_left.x, bottom + circle.diameter as i32);
+        Triangle::new(center, Point::new(right, center.y), tip)
+            .into_styled(style)
+            .draw(&mut area)?;
+        Ok(())
+    }
+
+    fn draw_seg_colon<D>(&self, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 幅を狭くする
+        let Size { width, height } = area.bounding_box().size;
+        let n_width = scale_ceil(width, self.point_width_rate);
+        let n_size = Size::new(n_width, height);
+        let mut area = area.cropped(&Rectangle::new(Point::new(0, 0), n_size));
+
+        // 高さの1/3と2/3の位置に丸を描画
+        let Size { width, height } = area.bounding_box().size;
+        let style = self.segment_style(color);
+        for center_y in [height / 3, height * 2 / 3] {
+            let center = Point::new((width / 2) as i32, center_y as i32);
+            Circle::with_center(center, width)
+                .into_styled(style)
+                .draw(&mut area)?;
+        }
+        Ok(())
+    }
+
+    fn draw_seg_degree<D>(&self, color: C, area: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 幅を狭くする
+        let Size { width, height } = area.bounding_box().size;
+        let n_width = scale_ceil(width, self.point_width_rate);
+        let n_size = Size::new(n_width, height);
+        let mut area = area.cropped(&Rectangle::new(Point::new(0, 0), n_size));
+
+        // 上端に、中空の丸を描画
+        let Size { width, .. } = area.bounding_box().size;
+        let stroke_width = (width / 4).max(1);
+        let style = PrimitiveStyleBuilder::new()
+            .stroke_color(color)
+            .stroke_width(stroke_width)
+            .build();
+        Circle::new(Point::zero(), width)
+            .into_styled(style)
+            .draw(&mut area)?;
+        Ok(())
+    }
+
+    /// セルの大きさから、マージンを除いた描画領域を求める。
+    fn inner_area(&self, cell: Size) -> Rectangle {
+        let Size { width, height } = cell;
+        let top_margin = scale_ceil(height, self.top_margin_rate);
+        let left_margin = scale_ceil(width, self.left_margin_rate);
+        let top_left = Point::new(left_margin as i32, top_margin as i32);
+        let size = Size::new(
+            width.saturating_sub(left_margin * 2),
+            height.saturating_sub(top_margin * 2),
+        );
+        Rectangle::new(top_left, size)
+    }
+
+    /// seg_patで指定したセグメント(bit0=A .. bit6=G)を描画する。
+    /// colorsは、各セグメントの色(colors[0]=A .. colors[6]=G)
+    fn draw_seg_pattern<D>(
+        &self,
+        seg_pat: u8,
+        colors: &[C; 7],
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let polygons = self.segment_polygons(area.bounding_box().size);
+        self.draw_seg_polygons(&polygons, seg_pat, colors, area)
+    }
+
+    /// draw_seg_patternと同じだが、各セグメントの頂点に、求めておいたpolygonsを使う。
+    fn draw_seg_polygons<D>(
+        &self,
+        polygons: &SegmentPolygons,
+        seg_pat: u8,
+        colors: &[C; 7],
+        area: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 同じ色のセグメントが続く間は、同じスタイルを使い回す。
+        let mut style_color = colors[0];
+        let mut style = self.segment_style(style_color);
+        for (i, (points, color)) in polygons.iter().zip(colors).enumerate() {
+            if seg_pat & (1 << i) != 0 {
+                if *color != style_color {
+                    style_color = *color;
+                    style = self.segment_style(style_color);
+                }
+                self.draw_polygon(points, style, area)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 文字列を描画する。draw_string本体。
+    /// <戻り値>
+    /// 正常の場合、描画後のカーソル位置と、未対応のため無視した文字数を返す。
+    fn draw_text<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        polygons: Option<&SegmentPolygons>,
+        target: &mut D,
+    ) -> Result<(Point, usize), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_glyph_line(self.glyphs(text), pos, baseline, &[], polygons, target)
+    }
+
+    /// 字形の並びを、一行に描画する。Noneは、未対応の文字として数える。
+    /// glyph_colorsを指定した場合、i番目に描画する字形を、glyph_colors[i]の色で描画する。
+    /// 字形の数より短い場合は、先頭から繰り返して使う。空の場合は、文字色で描画する。
+    /// <戻り値>
+    /// draw_textと同じ。
+    fn draw_glyph_line<I, D>(
+        &self,
+        glyphs: I,
+        pos: Point,
+        baseline: Baseline,
+        glyph_colors: &[C],
+        polygons: Option<&SegmentPolygons>,
+        target: &mut D,
+    ) -> Result<(Point, usize), D::Error>
+    where
+        I: Iterator<Item = Option<Glyph>> + Clone,
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_glyph_line_tracked(glyphs, pos, baseline, glyph_colors, polygons, target)
+            .map_err(|(error, _)| error)
+    }
+
+    /// draw_glyph_lineの本体。
+    /// <戻り値>
+    /// 正常の場合は、draw_textと同じ。エラーの場合は、描画先のエラーと、それまでに
+    /// 描画し終えた字形の数を返す。
+    fn draw_glyph_line_tracked<I, D>(
+        &self,
+        glyphs: I,
+        pos: Point,
+        baseline: Baseline,
+        glyph_colors: &[C],
+        polygons: Option<&SegmentPolygons>,
+        target: &mut D,
+    ) -> Result<(Point, usize), (D::Error, usize)>
+    where
+        I: Iterator<Item = Option<Glyph>> + Clone,
+        D: DrawTarget<Color = C>,
+    {
+        let mut glyph_colors = glyph_colors.iter().cycle();
+        let mut next_colors = || [*glyph_colors.next().unwrap_or(&self.text_color); 7];
+        let (advance, text_width) = self.line_width(glyphs.clone().flatten());
+        let mut cur_pos = self.line_top_left(pos, baseline, text_width);
+        let mut prev_pos = cur_pos;
+        let mut started = false;
+        let mut skipped = 0;
+        let mut drawn = 0;
+        let mut full = 0;
+        let line_x = cur_pos.x;
+        if !self.fits_coordinates(cur_pos, text_width) {
+            let skipped = glyphs.filter(Option::is_none).count();
+            return Ok((
+                self.next_position(Point::new(line_x, pos.y), advance),
+                skipped,
+            ));
+        }
+        // 回転する場合は、文字列全体を、回転しない場合の範囲ごと回転する。
+        let line_area = Rectangle::new(cur_pos, Size::new(text_width, self.size.height));
+        let target = &mut Rotated::new(target, self.rotation, line_area);
+        // 左右反転する場合は、文字列全体の鏡像になるよう、セルの位置も反転する。
+        let overhang = self.overhang();
+        let place = |cell_pos: Point, width: u32| {
+            if self.mirror_x {
+                let x = line_x * 2 + text_width as i32 - cell_pos.x - (width + overhang) as i32;
+                Point::new(x, cell_pos.y)
+            } else {
+                cell_pos
+            }
+        };
+        for glyph in glyphs {
+            if glyph == Some(Glyph::AttachedPoint) {
+                // 直前の数字の右下に重ねて描画するので、背景は塗らない。
+                let offset = Point::new(self.attached_point_offset() as i32, 0);
+                self.draw_cell(
+                    place(prev_pos + offset, self.size.width),
+                    glyph,
+                    false,
+                    &next_colors(),
+                    polygons,
+                    target,
+                )
+                .map_err(|error| (error, drawn))?;
+                drawn += 1;
+                continue;
+            }
+            if glyph.is_none() {
+                skipped += 1;
+            } else {
+                // 文字間の間隔は、2文字目以降の前に入れる。
+                if started {
+                    let gap = self.char_spacing;
+                    self.fill_gap(place(cur_pos, gap), gap, target)
+                        .map_err(|error| (error, drawn))?;
+                    cur_pos = offset_x(cur_pos, gap);
+                }
+                started = true;
+            }
+            let cell_pos = place(cur_pos, self.size.width);
+            let colors = match glyph {
+                Some(_) => next_colors(),
+                None => [self.text_color; 7],
+            };
+            let w = self
+                .draw_cell(cell_pos, glyph, true, &colors, polygons, target)
+                .map_err(|error| (error, drawn))?;
+            if glyph.is_some() {
+                self.draw_debug_cell(cell_pos, target)
+                    .map_err(|error| (error, drawn))?;
+            }
+            prev_pos = cur_pos;
+            cur_pos += Size::new(w, 0);
+            // with_scaleの幅の端数を配分した分だけ、次の文字をずらす。
+            if glyph.is_some_and(|glyph| self.is_full_width(glyph)) {
+                full += 1;
+                let extra = self.fraction_width(full) - self.fraction_width(full - 1);
+                self.fill_gap(place(cur_pos, extra), extra, target)
+                    .map_err(|error| (error, drawn))?;
+                cur_pos += Size::new(extra, 0);
+            }
+            drawn += 1;
+        }
+        Ok((
+            self.next_position(Point::new(line_x, pos.y), advance),
+            skipped,
+        ))
+    }
+
+    /// posを左上隅とするセルに、字形を一文字描画する。
+    /// <引数>
+    /// * pos: セルの左上隅
+    /// * glyph: 描画する字形。Noneの場合は、背景だけを塗る。
+    /// * clear: trueの場合、描画の前にセルを背景色で塗る。
+    /// * colors: 点灯する各セグメントの色(draw_glyphと同じ)
+    /// * polygons: 各セグメントの頂点(draw_glyphと同じ)
+    /// * target: 描画対象
+    ///
+    /// <戻り値>
+    /// 正常の場合、描画した幅を返す。
+    fn draw_cell<D>(
+        &self,
+        pos: Point,
+        glyph: Option<Glyph>,
+        clear: bool,
+        colors: &[C; 7],
+        polygons: Option<&SegmentPolygons>,
+        target: &mut D,
+    ) -> Result<u32, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let tilt_overhang = transform::overhang(self.effective_tilt(), self.size.height);
+        let map = CellMap {
+            size: self.size,
+            shear: self.slant,
+            mirror_x: self.mirror_x,
+            extra_width: tilt_overhang,
+        };
+        // 幅の狭い字形は、セルの右側だけを使うよう、セルを左へずらして描画する。
+        let shift = glyph.map_or(0, |glyph| self.narrow_shift(glyph));
+        let origin = if self.mirror_x {
+            pos + Point::new(shift as i32, 0)
+        } else {
+            pos - Point::new(shift as i32, 0)
+        };
+        if clear {
+            if let Some(bg_color) = self.background_color {
+                // 縦のセグメントを傾ける場合は、背景も同じだけ傾けて塗る。
+                let used =
+                    Rectangle::new(Point::new(shift as i32, 0), self.size - Size::new(shift, 0));
+                let mut bg_target =
+                    Transformed::new(target, self.background_map(self.size), origin);
+                self.fill_background(&mut bg_target, &used, bg_color)?;
+            }
+        }
+        // 画面の端にかかるセルも、大きさを変えずに描画し、はみ出す部分だけを捨てる。
+        let mut cell_target = Transformed::new(target, map, origin);
+        match glyph {
+            Some(glyph) => self
+                .draw_glyph(glyph, colors, polygons, &mut cell_target)
+                .map(|width| width - shift),
+            None => Ok(0),
+        }
+    }
+
+    /// with_debug_cellsの色を指定した場合、posを左上隅とするセルと、その描画領域の枠を描く。
+    fn draw_debug_cell<D>(&self, pos: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if let Some(color) = self.debug_cells {
+            let style = PrimitiveStyle::with_stroke(color, 1);
+            Rectangle::new(pos, self.size)
+                .into_styled(style)
+                .draw(target)?;
+            self.inner_area(self.size)
+                .translate(pos)
+                .into_styled(style)
+                .draw(target)?;
+        }
+        Ok(())
+    }
+
+    /// posを左上隅とする、幅widthで文字の高さの領域を、背景色で塗る。
+    /// 背景色が無い場合は、何もしない。傾ける場合は、文字のセルと同じように傾ける。
+    fn fill_gap<D>(&self, pos: Point, width: u32, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        match self.background_color {
+            Some(bg_color) if width > 0 => {
+                let map = self.background_map(Size::new(width, self.size.height));
+                let area = Rectangle::new(Point::zero(), map.size);
+                self.fill_background(&mut Transformed::new(target, map, pos), &area, bg_color)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// セグメントや小数点をcolorで描画するときの、スタイル
+    fn segment_style(&self, color: C) -> PrimitiveStyle<C> {
+        match self.segment_style {
+            SegmentStyle::Fill => PrimitiveStyle::with_fill(color),
+            SegmentStyle::Stroke { width } => PrimitiveStyleBuilder::new()
+                .stroke_color(color)
+                .stroke_width(width)
+                .stroke_alignment(StrokeAlignment::Inside)
+                .build(),
+        }
+    }
+
+    /// セルの座標の範囲areaを、背景色で塗る。グラデーションを指定した場合は、
+    /// セルの上端から下端まで、行ごとに色を変えて塗る。
+    fn fill_background<D>(
+        &self,
+        target: &mut Transformed<'_, D>,
+        area: &Rectangle,
+        bg_color: C,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        match (self.background_gradient, self.mix) {
+            (Some((top, bottom)), Some(mix)) => {
+                let last_row = self.size.height.saturating_sub(1).max(1) as i32;
+                for row in area.rows() {
+                    let alpha = 255 - (row.clamp(0, last_row) * 255 / last_row);
+                    let line = Rectangle::new(
+                        Point::new(area.top_left.x, row),
+                        Size::new(area.size.width, 1),
+                    );
+                    target.fill_solid(&line, mix(top, bottom, alpha as u8))?;
+                }
+                Ok(())
+            }
+            _ => target.fill_solid(area, bg_color),
+        }
+    }
+
+    /// 大きさsizeの背景を塗るときの、座標の変換。
+    /// 全体の傾きと、縦のセグメントの傾きを合わせた分だけ傾ける。
+    fn background_map(&self, size: Size) -> CellMap {
+        let shear = self.slant + self.effective_tilt();
+        CellMap {
+            size,
+            shear,
+            mirror_x: self.mirror_x,
+            extra_width: self.overhang() - transform::overhang(shear, self.size.height),
+        }
+    }
+
+    /// 傾けた場合に、字形がセルの右側にはみ出す幅。
+    fn overhang(&self) -> u32 {
+        let height = self.size.height;
+        transform::overhang(self.slant, height) + transform::overhang(self.effective_tilt(), height)
+    }
+
+    /// 縦のセグメントの傾き。SegmentShape::Pointed以外では傾けない。
+    fn effective_tilt(&self) -> f32 {
+        match self.segment_shape {
+            SegmentShape::Pointed => self.segment_tilt,
+            _ => 0.0,
+        }
+    }
+
+    /// 幅の狭いセルで描画する字形の場合、セルを左へずらす幅を返す。他の字形は0。
+    fn narrow_shift(&self, glyph: Glyph) -> u32 {
+        match glyph {
+            Glyph::Segments(seg_pat)
+                if self.proportional && seg_pat != 0 && seg_pat & !0b0000_0110 == 0 =>
+            {
+                let width = self.size.width;
+                let left_margin = scale_ceil(width, self.left_margin_rate);
+                let inner_width = width.saturating_sub(left_margin * 2);
+                let seg_width = scale_ceil(inner_width, self.line_width_rate);
+                width.saturating_sub(left_margin * 2 + seg_width)
+            }
+            Glyph::Blank { shift, .. } => shift,
+            // draw_signedの符号のセルの幅は、sign_width_rateで決まる。
+            Glyph::Sign(_) => {
+                let width = self.size.width;
+                width - scale_ceil(width, self.sign_width_rate).min(width)
+            }
+            _ => 0,
+        }
+    }
+
+    /// 字形を一文字描画する。
+    /// <引数>
+    /// * glyph: 描画する字形
+    /// * colors: 点灯する各セグメントの色(colors[0]=A .. colors[6]=G)。
+    ///   小数点、コロン、度記号は、colors[0]の色で描画する。ただし、区切りの色を
+    ///   指定した場合、小数点とコロンはその色で描画する。
+    /// * polygons: 求めておいた各セグメントの頂点。Noneの場合は、その場で求める。
+    /// * area: 描画対象のDrawTargetの可変参照
+    ///
+    /// <戻り値>
+    /// 正常の場合、描画した幅を返す。DrawTarget.draw()のエラーの可能性あり。
+    ///
+    fn draw_glyph<D>(
+        &self,
+        glyph: Glyph,
+        colors: &[C; 7],
+        polygons: Option<&SegmentPolygons>,
+        area: &mut D,
+    ) -> Result<u32, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // areaから、マージンを削除して領域を再定義
+        let all_area_width = area.bounding_box().size.width;
+        let inner = self.inner_area(area.bounding_box().size);
+        let size = inner.size;
+        let mut area = area.cropped(&inner);
+        let colors = match (glyph, self.separator_color) {
+            (Glyph::Point | Glyph::AttachedPoint | Glyph::Colon | Glyph::Comma, Some(color)) => {
+                [color; 7]
+            }
+            _ => *colors,
+        };
+        let colors = &self.dimmed_colors(&colors);
+        // 点灯する部分は、走査線の行の色を置き換えて描画する。
+        let scan_color = self
+            .scanlines
+            .map(|color| self.dimmed_colors(&[color; 7])[0]);
+        let mut area = Scanlines::new(&mut area, scan_color);
+        //　描画
+        match glyph {
+            Glyph::Point | Glyph::AttachedPoint => {
+                match (self.point_as_background, self.background_color) {
+                    (false, _) => self.draw_seg_point(colors[0], &mut area)?,
+                    (true, Some(bg_color)) => self.draw_seg_point(bg_color, area.target)?,
+                    (true, None) => {}
+                }
+            }
+            Glyph::Colon => self.draw_seg_colon(colors[0], &mut area)?,
+            Glyph::Comma => self.draw_seg_comma(colors[0], &mut area)?,
+            Glyph::Degree => self.draw_seg_degree(colors[0], &mut area)?,
+            Glyph::Segments(seg_pat) => {
+                let computed;
+                let polygons = match polygons {
+                    Some(polygons) => polygons,
+                    None => {
+                        computed = self.segment_polygons(size);
+                        &computed
+                    }
+                };
+                if let Some(off_color) = self.off_color {
+                    // 幅の狭い字形では、隣のセルにかかる消灯セグメントは描かない。
+                    let off_pat = if self.narrow_shift(glyph) > 0 {
+                        0b0000_0110
+                    } else {
+                        0b0111_1111
+                    };
+                    self.draw_seg_polygons(polygons, off_pat, &[off_color; 7], area.target)?;
+                }
+                self.draw_seg_polygons(polygons, seg_pat, colors, &mut area)?;
+                if let Some(highlight) = self.highlight {
+                    let highlight = self.dimmed_colors(&[highlight; 7])[0];
+                    self.draw_seg_highlight(polygons, seg_pat, highlight, size, &mut area)?;
+                }
+            }
+            Glyph::Sign(false) | Glyph::Blank { .. } => {}
+            Glyph::Sign(true) => {
+                let g = match polygons {
+                    Some(polygons) => polygons[6],
+                    None => self.segment_polygons(size)[6],
+                };
+                // 符号のセルは、セルの右側だけを使う。狭すぎて短くしきれない場合は、
+                // 符号のセルからはみ出す部分を描かない。
+                let shift = self.narrow_shift(glyph);
+                let left = shift as i32 - inner.top_left.x;
+                let sign_width = self.size.width - shift;
+                let used = Rectangle::new(Point::new(left, 0), Size::new(sign_width, size.height));
+                let center_x = left + (sign_width as i32 - 1) / 2;
+                let bar = sign_bar(&g, shift, center_x);
+                let style = self.segment_style(colors[6]);
+                self.draw_polygon(&bar, style, &mut area.clipped(&used))?;
+            }
+            Glyph::Plus => {
+                let g = match polygons {
+                    Some(polygons) => polygons[6],
+                    None => self.segment_polygons(size)[6],
+                };
+                let style = self.segment_style(colors[6]);
+                self.draw_polygon(&g, style, &mut area)?;
+                // 縦の線は、Gと重ならないよう、Gの上と下に分けて描く。
+                let bar = plus_bar(&g);
+                let left = bar[5].x - 1;
+                let bar_width = (bar[1].x - left + 2) as u32;
+                for (top, bottom) in [(bar[0].y, g[1].y - 1), (g[5].y + 1, bar[3].y)] {
+                    if top <= bottom {
+                        let height = (bottom - top + 1) as u32;
+                        let rows =
+                            Rectangle::new(Point::new(left, top), Size::new(bar_width, height));
+                        self.draw_polygon(&bar, style, &mut area.clipped(&rows))?;
+                    }
+                }
+            }
+        }
+
+        let draw_width = match glyph {
+            Glyph::Point | Glyph::Colon | Glyph::Comma | Glyph::Degree => {
+                self.point_advance(all_area_width)
+            }
+            Glyph::Segments(_) | Glyph::Plus | Glyph::Sign(_) => all_area_width,
+            Glyph::Blank { width, shift } => width + shift,
+            Glyph::AttachedPoint => 0,
+        };
+        Ok(draw_width)
+    }
+
+    /// 明るさの設定に応じて、点灯するセグメントの色を背景色に近づける。
+    fn dimmed_colors(&self, colors: &[C; 7]) -> [C; 7] {
+        match (self.brightness, self.mix) {
+            (Some((level, black)), Some(mix)) if level < 255 => {
+                let bg = self.background_color.unwrap_or(black);
+                colors.map(|color| mix(color, bg, level))
+            }
+            _ => *colors,
+        }
+    }
+
+    /// 字形が、数字の幅のセルを使う通常の幅の文字か。with_scaleの端数を配分する文字。
+    fn is_full_width(&self, glyph: Glyph) -> bool {
+        match glyph {
+            Glyph::Segments(_) | Glyph::Sign(_) | Glyph::Plus => true,
+            Glyph::Blank { width, shift } => width + shift == self.size.width,
+            _ => false,
+        }
+    }
+
+    /// 通常の幅の文字をcount個並べたときに、with_scaleの幅の端数から加える幅。
+    fn fraction_width(&self, count: u32) -> u32 {
+        ((count as u64 * self.width_fraction as u64 + 0x8000) >> 16) as u32
+    }
+
+    /// 字形の幅を返す。measure_string用。
+    fn glyph_width(&self, glyph: Glyph) -> u32 {
+        match glyph {
+            Glyph::Point | Glyph::Comma => self.calc_point_width(),
+            Glyph::Colon | Glyph::Degree => self.calc_colon_width(),
+            Glyph::AttachedPoint => 0,
+            Glyph::Segments(_) | Glyph::Sign(_) => self.size.width - self.narrow_shift(glyph),
+            Glyph::Blank { width, .. } => width,
+            Glyph::Plus => self.size.width,
+        }
+    }
+
+    /// 文字列を、一文字ずつ字形に変換するイテレータを返す。未対応の文字はNone。
+    fn glyphs<'a>(&'a self, text: &'a str) -> Glyphs<'a> {
+        Glyphs {
+            chars: text.chars().peekable(),
+            seg_pats: &self.seg_pats,
+            blank_leading_zeros: self.blank_leading_zeros,
+            attached_point: self.attached_point,
+            leading: true,
+            after_digit: false,
+        }
+    }
+
+    /// 字形の並びを一行に描画したときの、カーソルの進む幅と、描画範囲の幅を返す。
+    /// 描画範囲の幅は、傾けた場合にはみ出す部分も含む。
+    fn line_width(&self, glyphs: impl Iterator<Item = Glyph>) -> (u32, u32) {
+        let mut width = 0;
+        let mut count = 0;
+        let mut full = 0;
+        for glyph in glyphs {
+            width += self.glyph_width(glyph);
+            if glyph != Glyph::AttachedPoint {
+                count += 1;
+            }
+            if self.is_full_width(glyph) {
+                full += 1;
+            }
+        }
+        width += self.fraction_width(full);
+        if count > 1 {
+            width = width.saturating_add(self.char_spacing.saturating_mul(count - 1));
+        }
+        if count > 0 {
+            (width, width.saturating_add(self.overhang()))
+        } else {
+            (width, width)
+        }
+    }
+
+    /// 一行を描画した後のカーソル位置。advanceは、回転しない場合にカーソルの進む幅。
+    /// 90度と270度の回転では、回転後の文字列の幅(数字の高さ)だけ進む。
+    fn next_position(&self, pos: Point, advance: u32) -> Point {
+        let advance = match self.rotation {
+            Rotation::Deg90 | Rotation::Deg270 if advance > 0 => self.size.height,
+            _ => advance,
+        };
+        offset_x(pos, advance)
+    }
+
+    /// 実際に描画する幅でのmeasure_string。with_measure_narrow_oneの指定を無視する。
+    fn drawn_metrics(&self, text: &str, pos: Point, baseline: Baseline) -> TextMetrics {
+        self.with_measure_narrow_one(false)
+            .measure_string(text, pos, baseline)
+    }
+
+    /// 原点pos、幅text_widthの一行を描画するときの、回転前の一行の範囲の左上隅。
+    /// Origin::Centerでは、回転後の範囲の中央がposになるようにする。
+    fn line_top_left(&self, pos: Point, baseline: Baseline, text_width: u32) -> Point {
+        match self.origin {
+            Origin::TopLeft => pos - Point::new(0, self.baseline_offset(baseline)),
+            Origin::Center => {
+                let line_area = Rectangle::new(pos, Size::new(text_width, self.size.height));
+                let size = transform::rotate_rect(self.rotation, line_area).size;
+                // Rectangle::center()と同じく、偶数の幅の中央は、左上寄りの点にする。
+                let half = |len: u32| (len.saturating_sub(1) / 2) as i32;
+                pos - Point::new(half(size.width), half(size.height))
+            }
+        }
+    }
+
+    /// 左上隅top_leftから、長さwidthの一行(縦書きでは一列)が、回転や傾きを含めて
+    /// 座標の範囲に収まるか。文字間の間隔が極端に大きい場合など、収まらない行は、
+    /// どの描画先にも描画できない。
+    fn fits_coordinates(&self, top_left: Point, width: u32) -> bool {
+        let extent = width as i64 + 2 * (self.size.width + self.size.height) as i64;
+        let (x, y) = (top_left.x as i64, top_left.y as i64);
+        x.abs().max(y.abs()) + extent <= i32::MAX as i64
+    }
+
+    /// posを左上隅として字形を一文字描画するときの、回転前のセルの範囲。
+    fn cell_area(&self, pos: Point, glyph: Glyph) -> Rectangle {
+        let width = self.glyph_width(glyph) + self.overhang();
+        Rectangle::new(pos, Size::new(width, self.size.height))
+    }
+
+    /// Baselineに応じた、原点からセルの上端までの垂直方向の距離を返します。
+    ///
+    /// draw_stringなどは、原点からこの距離だけ上を、セルの上端として描画します。
+    /// 他のフォントと位置を揃えるときに使えます。Alphabeticでは、セグメントDの下端の行に
+    /// 原点が来るように、下のマージンを除いた距離になります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::BinaryColor};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+    /// assert_eq!(font.baseline_offset(Baseline::Top), 0);
+    /// assert_eq!(font.baseline_offset(Baseline::Bottom), 19);
+    /// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// Text::with_baseline("8", Point::new(0, 30), font, Baseline::Alphabetic)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// // 数字の下端の行が、原点の行になる
+    /// assert_eq!(display.affected_area().bottom_right().unwrap().y, 30);
+    /// ```
+    pub fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        let bottom = self.size.height.saturating_sub(1);
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Middle => (bottom / 2) as i32,
+            Baseline::Bottom => bottom as i32,
+            Baseline::Alphabetic => {
+                let inner = self.inner_area(self.size);
+                (inner.top_left.y + inner.size.height as i32 - 1).max(0)
+            }
+        }
+    }
+
+    /// 小数点の幅。draw_glyphで小数点を描画したときの幅と、同じpoint_advanceで求める。
+    fn calc_point_width(&self) -> u32 {
+        self.point_advance(self.size.width)
+    }
+
+    /// 幅cell_widthのセルに、小数点を描画したときのカーソルの進む幅。
+    /// 左右のマージンと、描画領域の幅にpoint_width_rateを掛けた小数点の幅の和。
+    /// マージンだけでセルの幅を超える場合は、セルの幅とする。
+    fn point_advance(&self, cell_width: u32) -> u32 {
+        let inner_width = self.inner_area(Size::new(cell_width, 0)).size.width;
+        let margins = cell_width - inner_width;
+        margins + scale_ceil(inner_width, self.point_width_rate)
+    }
+
+    /// 直前の数字に付ける小数点を、右端がセルの右端に接するように描画するための、
+    /// 直前の数字のセルの原点からの水平方向の距離。
+    fn attached_point_offset(&self) -> u32 {
+        let width = self.size.width;
+        let left_margin = scale_ceil(width, self.left_margin_rate);
+        let inner_width = width.saturating_sub(left_margin * 2);
+        let p_width = scale_ceil(inner_width, self.point_width_rate);
+        width.saturating_sub(left_margin + p_width)
+    }
+
+    /// コロンと度記号の幅。小数点と同じく、point_width_rateで決まる。
+    fn calc_colon_width(&self) -> u32 {
+        self.calc_point_width()
+    }
+}
+
+/// 7セグメントの各セグメント
+///
+/// ```text
+///  AAA
+/// F   B
+/// F   B
+///  GGG
+/// E   C
+/// E   C
+///  DDD
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+}
+
+impl Segment {
+    /// セグメントパターンでの、このセグメントのビットを返します。(bit0=A .. bit6=G)
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// セグメントの尖った端の長さの、セグメントの太さに対する比率
+///
+/// 横のセグメントは、両端をこの長さだけ左右の縦のセグメントとの間に空けて描きます。
+pub const SEGMENT_TAPER_RATE: f32 = TAPER_RATE.0 as f32 / TAPER_RATE.1 as f32;
+
+/// 縦のセグメントの長さの、描画領域の高さの半分に対する比率
+pub const SEGMENT_LENGTH_RATE: f32 = VERT_LENGTH_RATE.0 as f32 / VERT_LENGTH_RATE.1 as f32;
+
+/// 縦のセグメントの上端の、描画領域の高さの半分に対する位置の比率
+///
+/// 上端は、この比率の位置から、さらにセグメントの太さの半分だけ下になります。
+pub const SEGMENT_OFFSET_RATE: f32 = VERT_OFFSET_RATE.0 as f32 / VERT_OFFSET_RATE.1 as f32;
+
+/// SEGMENT_TAPER_RATEの分子と分母。ピクセル単位の計算は、整数のまま行う。
+const TAPER_RATE: (u32, u32) = (3, 5);
+/// SEGMENT_LENGTH_RATEの分子と分母
+const VERT_LENGTH_RATE: (u32, u32) = (4, 5);
+/// SEGMENT_OFFSET_RATEの分子と分母
+const VERT_OFFSET_RATE: (u32, u32) = (1, 20);
+
+/// 文字を表示するときに点灯するセグメントのパターン(bit0=A .. bit6=G)を返します。
+///
+/// 標準のパターンで、数字(16進数を含む)、マイナス記号、空白と、状態表示用の英字に対応します。
+/// 全角の数字も、半角の数字と同じパターンになります。セグメントで表せない文字(小数点・コロン・度記号など)はNoneです。
+/// DrawTargetを使わない、セグメント単位で制御する表示器の駆動に使えます。
+///
+/// ```
+/// # use font_7seg::{segments_for, Segment};
+/// assert_eq!(segments_for('1'), Some(Segment::B.bit() | Segment::C.bit()));
+/// assert_eq!(segments_for('-'), Some(Segment::G.bit()));
+/// assert_eq!(segments_for('５'), segments_for('5'));
+/// assert_eq!(segments_for('x'), None);
+/// ```
+pub fn segments_for(c: char) -> Option<u8> {
+    match Glyph::from_char(to_halfwidth(c), &SEG_PATS)? {
+        Glyph::Segments(pattern) => Some(pattern),
+        _ => None,
+    }
+}
+
+/// 文字を描画できるかを返します。
+///
+/// draw_stringが無視する未対応の文字ではfalse、それ以外(数字、小数点、記号、空白、
+/// 状態表示用の英字、全角の数字など)ではtrueです。センサーなどから来た文字列を、
+/// 描画する前に確かめたり、未対応の文字を置き換えたりするのに使えます。
+///
+/// ```
+/// # use font_7seg::is_supported;
+/// assert!(is_supported('7'));
+/// assert!(is_supported('.'));
+/// assert!(is_supported('９'));
+/// assert!(!is_supported('x'));
+/// ```
+pub fn is_supported(c: char) -> bool {
+    Glyph::from_char(to_halfwidth(c), &SEG_PATS).is_some()
+}
+
+/// 表示器の確認用に、セグメントを一つずつ順に点灯するパターンを返す状態機械
+///
+/// next()を呼ぶごとに、A→B→C→D→E→F→G→小数点→全点灯の順に、次のパターンを返します。
+/// 全点灯の次は、Aに戻って繰り返します。パターンはdraw_masksと同じく、bit0〜bit6が
+/// セグメントA〜G、bit7が小数点なので、一コマごとにdraw_masksに渡すと、電源投入時の
+/// セグメントの点灯確認の表示になります。
+///
+/// ```
+/// # use embedded_graphics::{prelude::*, pixelcolor::BinaryColor};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # use font_7seg::{Font7Seg, Segment, SegmentSweep};
+/// let mut sweep = SegmentSweep::new();
+/// assert_eq!(sweep.next(), Some(Segment::A.bit()));
+/// assert_eq!(sweep.next(), Some(Segment::B.bit()));
+/// let rest: [u8; 8] = core::array::from_fn(|_| sweep.next().unwrap());
+/// assert_eq!(rest, [0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0xff, 0x01]);
+///
+/// // 一コマ分を、4桁すべてに同じパターンで表示する
+/// let font = Font7Seg::new(Size::new(10, 20), BinaryColor::On);
+/// let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// let mask = sweep.next().unwrap();
+/// font.draw_masks(&[mask; 4], Point::zero(), &mut display).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SegmentSweep {
+    step: u8,
+}
+
+impl SegmentSweep {
+    /// 一周の長さ(A〜G、小数点、全点灯)
+    pub const STEPS: u8 = 9;
+
+    /// セグメントAから始める状態を生成します。
+    pub const fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    /// セグメントAからやり直します。
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+}
+
+impl Iterator for SegmentSweep {
+    type Item = u8;
+
+    /// 現在のコマのパターンを返して、次のコマに進みます。終わりは無く、常にSomeです。
+    fn next(&mut self) -> Option<u8> {
+        let mask = match self.step {
+            0..=7 => 1 << self.step,
+            _ => 0xff,
+        };
+        self.step = (self.step + 1) % Self::STEPS;
+        Some(mask)
+    }
+}
+
+/// 描画の回転(時計回り)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// 回転しない
+    Deg0,
+    /// 90度
+    Deg90,
+    /// 180度
+    Deg180,
+    /// 270度
+    Deg270,
+}
+
+/// try_draw_string、try_new、Font7SegBuilder::buildのエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font7SegError<E = core::convert::Infallible> {
+    /// 描画対象のDrawTargetのエラー
+    Draw(E),
+    /// 未対応の文字
+    UnsupportedChar(char),
+    /// 小さすぎる数字のサイズ
+    InvalidSize(Size),
+    /// セルに収まらない、セグメントの太さやマージン、隙間の組み合わせ
+    InvalidGeometry,
+}
+
+/// draw_string_resumableで、描画先のエラーにより描画が途中で止まったときのエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialDraw<E> {
+    /// 描画先のDrawTargetのエラー
+    pub error: E,
+    /// 描画し終えた文字の数(未対応のため無視した文字も含みます)
+    pub drawn: usize,
+    /// 描画し終えた文字だけをdraw_stringした場合の、描画後のカーソル位置
+    pub position: Point,
+}
+
+/// セグメントの両端の形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentShape {
+    /// 三角形にとがらせる
+    Pointed,
+    /// とがらせず、長方形のままにする
+    Flat,
+    /// 半円で丸める
+    Rounded,
+}
+
+/// セグメントの描き方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentStyle {
+    /// 塗りつぶす
+    Fill,
+    /// 幅widthの輪郭だけを描く
+    Stroke {
+        /// 輪郭の幅(ピクセル単位)
+        width: u32,
+    },
+}
+
+/// draw_stringなどの原点の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Origin {
+    /// 文字列の左端。上下の位置は、Baseline引数で決める
+    TopLeft,
+    /// 文字列の範囲の上下左右の中央
+    Center,
+}
+
+/// 小数点の形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointShape {
+    /// 丸
+    Circle,
+    /// 正方形
+    Square,
+}
+
+/// draw_temp_unitで描画する温度の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TempUnit {
+    /// 摂氏("°C")
+    Celsius,
+    /// 華氏("°F")
+    Fahrenheit,
+}
+
+/// 小数点の縦の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointPosition {
+    /// 下端(通常の小数点)
+    Bottom,
+    /// 上下中央
+    Middle,
+    /// 上端
+    Top,
+}
+
+/// 0〜F(16進)の各数字の、標準のセグメントパターン(bit0=A .. bit6=G)
+const SEG_PATS: [u8; 16] = [
+    0b0011_1111,
+    0b0000_0110,
+    0b0101_1011,
+    0b0100_1111,
+    0b0110_0110,
+    0b0110_1101,
+    0b0111_1101,
+    0b0010_0111,
+    0b0111_1111,
+    0b0110_1111,
+    0b0111_0111,
+    0b0111_1100,
+    0b0011_1001,
+    0b0101_1110,
+    0b0111_1001,
+    0b0111_0001,
+];
+
+/// マイナス記号のセグメントパターン(Gのみ)
+const SEG_PAT_MINUS: u8 = 0b0100_0000;
+
+/// 度記号を一桁の7セグメントで表すときのパターン(A、B、F、G)
+const SEG_PAT_DEGREE: u8 = 0b0110_0011;
+
+/// 等号のセグメントパターン(GとD)
+const SEG_PAT_EQUALS: u8 = 0b0100_1000;
+
+/// 下線のセグメントパターン(Dのみ)
+const SEG_PAT_UNDERSCORE: u8 = 0b0000_1000;
+
+/// 16進数以外の英字のセグメントパターン
+/// 大文字と小文字で字形が違うH、o、uは、その文字だけに対応する。
+fn letter_pattern(c: char) -> Option<u8> {
+    let pattern = match c {
+        'H' => 0b0111_0110,
+        'L' | 'l' => 0b0011_1000,
+        'P' | 'p' => 0b0111_0011,
+        'n' | 'N' => 0b0101_0100,
+        'o' => 0b0101_1100,
+        'r' | 'R' => 0b0101_0000,
+        't' | 'T' => 0b0111_1000,
+        'u' => 0b0001_1100,
+        'J' | 'j' => 0b0001_1110,
+        'y' | 'Y' => 0b0110_1110,
+        _ => return None,
+    };
+    Some(pattern)
+}
+
+/// 描画する字形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Glyph {
+    /// セグメントの組み合わせで表す字形(bit0=A .. bit6=G)
+    Segments(u8),
+    /// 小数点
+    Point,
+    /// コロン
+    Colon,
+    /// 桁区切りのカンマ
+    Comma,
+    /// 度記号
+    Degree,
+    /// プラス記号。セグメントGに、縦の線を重ねる。
+    Plus,
+    /// 直前の数字の右下に付ける小数点
+    AttachedPoint,
+    /// draw_signedの符号のセル。trueの場合、幅に合わせて短くしたセグメントGを描く。
+    Sign(bool),
+    /// 背景だけを塗るセル。draw_string_maskedで消した字形の、幅とnarrow_shiftを持つ。
+    Blank { width: u32, shift: u32 },
+}
+
+impl Glyph {
+    /// 字形を一桁の7セグメントで表すときの、セグメントのパターン(bit7=小数点)。
+    /// AttachedPointは、直前の数字に加える小数点だけのパターン。
+    fn mask(self) -> u8 {
+        match self {
+            Glyph::Segments(seg_pat) => seg_pat,
+            Glyph::Point | Glyph::Comma | Glyph::AttachedPoint => 0x80,
+            Glyph::Degree => SEG_PAT_DEGREE,
+            Glyph::Plus => SEG_PAT_MINUS,
+            Glyph::Sign(negative) => {
+                if negative {
+                    SEG_PAT_MINUS
+                } else {
+                    0
+                }
+            }
+            Glyph::Colon | Glyph::Blank { .. } => 0,
+        }
+    }
+
+    /// 文字に対応する字形を返す。未対応の文字はNone。
+    /// 数字の字形は、seg_patsから引く。
+    fn from_char(c: char, seg_pats: &[u8; 16]) -> Option<Self> {
+        if let Some(num) = c.to_digit(16) {
+            return Some(Glyph::Segments(seg_pats[num as usize]));
+        }
+        match c {
+            '.' => Some(Glyph::Point),
+            ':' => Some(Glyph::Colon),
+            ',' => Some(Glyph::Comma),
+            '°' => Some(Glyph::Degree),
+            '-' => Some(Glyph::Segments(SEG_PAT_MINUS)),
+            '+' => Some(Glyph::Plus),
+            '=' => Some(Glyph::Segments(SEG_PAT_EQUALS)),
+            '_' => Some(Glyph::Segments(SEG_PAT_UNDERSCORE)),
+            ' ' => Some(Glyph::Segments(0)),
+            _ => letter_pattern(c).map(Glyph::Segments),
+        }
+    }
+}
+
+/// 全角の数字(０〜９)と小数点(．)を、半角の文字に変換する。他の文字はそのまま返す。
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '０'..='９' | '．' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 文字列を、一文字ずつ字形に変換するイテレータ
+#[derive(Clone)]
+struct Glyphs<'a> {
+    chars: Peekable<Chars<'a>>,
+    seg_pats: &'a [u8; 16],
+    blank_leading_zeros: bool,
+    attached_point: bool,
+    /// まだ数字の先頭部分(先行する0の並び)にいるか
+    leading: bool,
+    /// 直前の文字が数字だったか
+    after_digit: bool,
+}
+
+impl Iterator for Glyphs<'_> {
+    type Item = Option<Glyph>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = to_halfwidth(self.chars.next()?);
+        let after_digit = core::mem::replace(&mut self.after_digit, c.is_ascii_hexdigit());
+        if c == '.' && after_digit && self.attached_point {
+            return Some(Some(Glyph::AttachedPoint));
+        }
+        if self.leading && c == '0' && self.blank_leading_zeros {
+            // 後ろに数字が続く0だけを消す。"0"や"0.5"の0は残す。
+            if matches!(self.chars.peek(), Some(&next) if to_halfwidth(next).is_ascii_hexdigit()) {
+                return Some(Some(Glyph::Segments(0)));
+            }
+        }
+        if c != '-' && c != '+' && c != ' ' {
+            self.leading = false;
+        }
+        Some(Glyph::from_char(c, self.seg_pats))
+    }
+}
+
+/// format_decimalのバッファの長さ(符号と10桁)
+const DECIMAL_BUF_LEN: usize = 11;
+
+/// 数値を、10進数の文字列に変換する。
+/// min_digitsに満たない桁は0で埋める。min_digitsは、最大10桁として扱う。
+fn format_decimal(
+    mut value: u32,
+    negative: bool,
+    min_digits: u8,
+    buf: &mut [u8; DECIMAL_BUF_LEN],
+) -> &str {
+    let min_digits = (min_digits as usize).min(DECIMAL_BUF_LEN - 1);
+    let mut start = buf.len();
+    while value > 0 || buf.len() - start < min_digits.max(1) {
+        start -= 1;
+        buf[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+    if negative {
+        start -= 1;
+        buf[start] = b'-';
+    }
+    // バッファには、ASCIIの数字とマイナス記号しか書き込んでいない。
+    core::str::from_utf8(&buf[start..]).unwrap_or("")
+}
+
+/// valueにrateを掛けて、切り上げた整数を返す。セルの大きさから、マージンや
+/// セグメントの太さを求めるのに使う。
+///
+/// fixed-pointフィーチャーでは、scale_ceil_fixedで、浮動小数点の演算を使わずに求める。
+fn scale_ceil(value: u32, rate: f32) -> u32 {
+    if cfg!(feature = "fixed-point") {
+        scale_ceil_fixed(value, rate)
+    } else {
+        scale_ceil_float(value, rate)
+    }
+}
+
+/// scale_ceilの、浮動小数点で計算する実装
+fn scale_ceil_float(value: u32, rate: f32) -> u32 {
+    (value as f32 * rate).ceil() as u32
+}
+
+/// scale_ceilの、整数だけで計算する実装
+///
+/// f32のビット列から仮数と指数を取り出し、f32の掛け算と同じく、積を仮数24ビットに
+/// 丸めてから切り上げるので、結果はscale_ceil_floatと一致する。
+fn scale_ceil_fixed(value: u32, rate: f32) -> u32 {
+    let bits = rate.to_bits();
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let frac = (bits & 0x7f_ffff) as u64;
+    if bits >> 31 != 0 || value == 0 || (exp == 0 && frac == 0) {
+        return 0;
+    }
+    if exp == 0xff {
+        // 無限大なら最大値、NaNなら0(f32からu32への変換と同じ)
+        return if frac == 0 { u32::MAX } else { 0 };
+    }
+    // rateは mantissa × 2^(exp - 150)。非正規化数は、指数を1として扱う。
+    let (mantissa, exp) = if exp == 0 {
+        (frac, 1 - 150)
+    } else {
+        (frac | 0x80_0000, exp - 150)
+    };
+    let (value, value_exp) = round_to_f32(value as u64);
+    let (product, product_exp) = round_to_f32(value * mantissa);
+    let exp = exp + value_exp + product_exp;
+    let ceiled = if exp >= 0 {
+        product
+            .checked_shl(exp as u32)
+            .filter(|p| p >> exp == product)
+    } else if exp > -64 {
+        let unit = 1u64 << -exp;
+        Some((product + unit - 1) >> -exp)
+    } else {
+        Some(1)
+    };
+    ceiled.map_or(u32::MAX, |v| v.min(u32::MAX as u64) as u32)
+}
+
+/// 整数valueを、f32と同じく、仮数24ビットに丸める(偶数丸め)。
+/// 戻り値は、仮数と、2の指数の組で、value ≒ 仮数 × 2^指数となる。
+fn round_to_f32(value: u64) -> (u64, i32) {
+    let bits = 64 - value.leading_zeros() as i32;
+    if bits <= 24 {
+        return (value, 0);
+    }
+    let mut shift = bits - 24;
+    let mut mantissa = value >> shift;
+    let rest = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if rest > half || (rest == half && mantissa & 1 == 1) {
+        mantissa += 1;
+        if mantissa == 1 << 24 {
+            mantissa >>= 1;
+            shift += 1;
+        }
+    }
+    (mantissa, shift)
+}
+
+/// rateを、小数部16ビットの固定小数点に変換する(端数は切り捨て)。
+/// f32のビット列を直接読むので、浮動小数点の演算は使わない。負の値は0とする。
+fn rate_to_fixed(rate: f32) -> u32 {
+    let bits = rate.to_bits();
+    let exp = ((bits >> 23) & 0xff) as i32;
+    if bits >> 31 != 0 || exp == 0 {
+        return 0;
+    }
+    // 値は mantissa × 2^(exp - 150)なので、2^16倍すると mantissa × 2^(exp - 134)
+    let mantissa = ((bits & 0x7f_ffff) | 0x80_0000) as u64;
+    let shift = exp - 134;
+    let fixed = if shift >= 0 {
+        mantissa << shift.min(40)
+    } else if shift > -32 {
+        mantissa >> -shift
+    } else {
+        0
+    };
+    fixed.min(u32::MAX as u64) as u32
+}
+
+/// tanの近似値を求める。±30度(±π/6)の範囲で、誤差は0.0001以下。
+fn tan_approx(x: f32) -> f32 {
+    let x2 = x * x;
+    x * (1.0 + x2 * (1.0 / 3.0 + x2 * (2.0 / 15.0 + x2 * (17.0 / 315.0))))
+}
+
+/// 比率を0.0〜maxの範囲に丸め込む。NaNは0.0とする。
+fn clamp_rate(rate: f32, max: f32) -> f32 {
+    rate.max(0.0).min(max)
+}
+
+impl<C> Font7Seg<C>
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    /// セグメントの斜めの辺を、アンチエイリアスして描画します。(初期値 false)
+    ///
+    /// 辺にかかるピクセルを、被覆率に応じて文字色と背景色を混ぜた色で描画します。
+    /// Rgb888と相互に変換できる色でのみ使えます。
+    /// 背景色が設定されていない場合は、混ぜる相手がないため、通常の描画になります。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::CharacterStyle}, pixelcolor::Rgb888};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let mut font = Font7Seg::new(Size::new(10, 20), Rgb888::WHITE);
+    /// font.set_background_color(Some(Rgb888::BLACK));
+    /// let draw = |font: Font7Seg<Rgb888>| {
+    ///     let mut display: MockDisplay<Rgb888> = MockDisplay::new();
+    ///     display.set_allow_overdraw(true);
+    ///     Text::with_baseline("8", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     display
+    /// };
+    /// let intermediate = |display: &MockDisplay<Rgb888>| {
+    ///     display.affected_area().points().any(|p| {
+    ///         let color = display.get_pixel(p);
+    ///         color != Some(Rgb888::WHITE) && color != Some(Rgb888::BLACK)
+    ///     })
+    /// };
+    /// // 中間色は、アンチエイリアスした場合だけ現れる
+    /// assert!(intermediate(&draw(font.with_antialiasing(true))));
+    /// assert!(!intermediate(&draw(font)));
+    /// // 無効にすると、初期値と同じ描画になる
+    /// draw(font.with_antialiasing(false)).assert_eq(&draw(font));
+    /// ```
+    pub fn with_antialiasing(mut self, antialiasing: bool) -> Self {
+        self.antialiasing = antialiasing;
+        self.mix = Some(mix_colors::<C>);
+        self
+    }
+
+    /// 点灯するセグメントの明るさを指定します。(初期値 1.0)
+    ///
+    /// 1.0で文字色そのまま、0.0で背景色(背景色がない場合は黒)となり、その間は
+    /// 二つの色を混ぜた色で描画します。夜間に表示を暗くする場合などに使えます。
+    /// 0.0〜1.0の範囲外の値は、範囲内に丸め込みます。NaNは0.0とみなします。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text, renderer::CharacterStyle}};
+    /// # use embedded_graphics::pixelcolor::{PixelColor, Rgb565, Rgb888};
+    /// # use embedded_graphics::mock_display::{ColorMapping, MockDisplay};
+    /// # use font_7seg::Font7Seg;
+    /// fn draw<C: PixelColor>(font: Font7Seg<C>) -> MockDisplay<C> {
+    ///     let mut display = MockDisplay::new();
+    ///     display.set_allow_overdraw(true);
+    ///     Text::with_baseline("8.", Point::zero(), font, Baseline::Top)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///     display
+    /// }
+    ///
+    /// fn check<C>(text_color: C, background: C)
+    /// where
+    ///     C: PixelColor + ColorMapping + From<Rgb888> + Into<Rgb888>,
+    /// {
+    ///     for bg in [None, Some(background)] {
+    ///         for antialiasing in [false, true] {
+    ///             let mut font = Font7Seg::new(Size::new(10, 20), text_color)
+    ///                 .with_slant(10.0)
+    ///                 .with_antialiasing(antialiasing);
+    ///             font.set_background_color(bg);
+    ///             // 1.0では、明るさを指定しない場合と同じ描画になる
+    ///             draw(font.with_brightness(1.0)).assert_eq(&draw(font));
+    ///             // 0.0では、点灯するセグメントも背景色(背景色がなければ黒)になる
+    ///             let dark = bg.unwrap_or(Rgb888::BLACK.into());
+    ///             let display = draw(font.with_brightness(0.0));
+    ///             assert!(display
+    ///                 .affected_area()
+    ///                 .points()
+    ///                 .all(|p| display.get_pixel(p).map_or(true, |color| color == dark)));
+    ///         }
+    ///     }
+    /// }
+    /// check(Rgb888::new(255, 160, 0), Rgb888::new(0, 0, 80));
+    /// check(Rgb565::new(31, 40, 0), Rgb565::new(0, 0, 10));
+    /// ```
+    pub fn with_brightness(mut self, level: f32) -> Self {
+        let level = (clamp_rate(level, 1.0) * 255.0).round() as u8;
+        self.brightness = Some((level, Rgb888::BLACK.into()));
+        self.mix = Some(mix_colors::<C>);
+        self
+    }
+
+    /// 各文字のセルの背景を、上端の色topから下端の色bottomへの、縦のグラデーションで
+    /// 塗ります。(初期値 なし)
+    ///
+    /// 背景色を塗る全ての場所(文字のセル、文字間の間隔、空白)で、セルの上端からの行に
+    /// 応じて二つの色を混ぜます。アンチエイリアスや明るさの計算で混ぜる背景色には、
+    /// 二つの色の中間の色を使います(背景色も、その色に設定します)。topとbottomが同じ色の
+    /// 場合は、set_background_colorで単色を指定した場合と同じです。回転した場合は、
+    /// グラデーションも文字と一緒に回転します。
+    ///
+    /// ```
+    /// # use embedded_graphics::{prelude::*, text::{Baseline, Text}, pixelcolor::Rgb888};
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # use font_7seg::Font7Seg;
+    /// let font = Font7Seg::new(Size::new(10, 20), Rgb888::RED)
+    ///     .with_background_gradient(Rgb888::BLUE, Rgb888::BLACK);
+    /// let mut display: MockDisplay<Rgb888> = MockDisplay::new();
+    /// Text::with_baseline(" ", Point::zero(), font, Baseline::Top)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::BLUE));
+    /// assert_eq!(display.get_pixel(Point::new(0, 19)), Some(Rgb888::BLACK));
+    /// ```
+    pub fn with_background_gradient(mut self, top: C, bottom: C) -> Self {
+        self.background_gradient = Some((top, bottom));
+        self.background_color = Some(mix_colors(top, bottom, 128));
+        self.mix = Some(mix_colors::<C>);
+        self
+    }
+
+    /// draw_string_blendedで、描画先の内容に重ねる不透明度を指定します。(初期値 255)
+    ///
+    /// 255で不透明、0で透明(描画先の色のまま)、その間は、文字の色と描画先の色を
+    /// alphaの割合で混ぜます。カメラ映像の上に重ねるメーターなどに使えます。
+    /// 描画先を読み出せない通常のdraw_stringでは、この設定によらず不透明です。
+    pub fn with_opacity(mut self, alpha: u8) -> Self {
+        self.opacity = alpha;
+        self.mix = Some(mix_colors::<C>);
+        self
+    }
+}
+
+/// A〜Gの各セグメントの頂点
+type SegmentPolygons = [[Point; 6]; 7];
+
+/// プラス記号の縦の線の頂点。横のセグメントgの頂点を、その中心で90度回した形。
+fn plus_bar(g: &[Point; 6]) -> [Point; 6] {
+    let center = Point::new((g[0].x + g[3].x) / 2, g[0].y);
+    let half_length = (g[3].x - g[0].x) / 2;
+    let half_width = (g[5].y - g[1].y) / 2;
+    let base = half_length - (g[1].x - g[0].x);
+    [
+        Point::new(center.x, center.y - half_length),
+        Point::new(center.x + half_width, center.y - base),
+        Point::new(center.x + half_width, center.y + base),
+        Point::new(center.x, center.y + half_length),
+        Point::new(center.x - half_width, center.y + base),
+        Point::new(center.x - half_width, center.y - base),
+    ]
+}
+
+/// 符号のセルのマイナス記号の頂点。横のセグメントgをdeltaだけ短くして、
+/// 左右の中央がcenter_xの列に来るように移した形。
+/// 両端の三角形の形を保つため、短くする幅は、中央の長方形の長さまでとする。
+fn sign_bar(g: &[Point; 6], delta: u32, center_x: i32) -> [Point; 6] {
+    let shorten = Point::new((delta as i32).min(g[2].x - g[1].x), 0);
+    let bar = [
+        g[0],
+        g[1],
+        g[2] - shorten,
+        g[3] - shorten,
+        g[4] - shorten,
+        g[5],
+    ];
+    let offset = Point::new(center_x - (bar[0].x + bar[3].x) / 2, 0);
+    bar.map(|p| p + offset)
+}
+
+/// parentの中の、parentの左上隅を原点とするrectの範囲。parentからはみ出す部分は除く。
+fn sub_area(parent: &Rectangle, rect: Rectangle) -> Rectangle {
+    Rectangle::new(parent.top_left + rect.top_left, rect.size).intersection(parent)
+}
+
+/// 奇数行のピクセルを、走査線の色で描画するラッパー。with_scanlines用。
+/// 色がNoneの場合は、そのまま描画する。
+struct Scanlines<'a, D, C> {
+    target: &'a mut D,
+    color: Option<C>,
+}
+
+impl<'a, D, C> Scanlines<'a, D, C> {
+    fn new(target: &'a mut D, color: Option<C>) -> Self {
+        Self { target, color }
+    }
+}
+
+impl<D: Dimensions, C> Dimensions for Scanlines<'_, D, C> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D, C> DrawTarget for Scanlines<'_, D, C>
+where
+    D: DrawTarget<Color = C>,
+    C: PixelColor,
+{
+    type Color = C;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        match self.color {
+            Some(scan_color) => self
+                .target
+                .draw_iter(pixels.into_iter().map(|Pixel(p, color)| {
+                    if p.y.rem_euclid(2) == 1 {
+                        Pixel(p, scan_color)
+                    } else {
+                        Pixel(p, color)
+                    }
+                })),
+            None => self.target.draw_iter(pixels),
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let scan_color = match self.color {
+            Some(scan_color) => scan_color,
+            None => return self.target.fill_solid(area, color),
+        };
+        // 一行ずつ、奇数の行を走査線の色で塗る
+        for y in area.rows() {
+            let line = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            );
+            let color = if y.rem_euclid(2) == 1 {
+                scan_color
+            } else {
+                color
+            };
+            self.target.fill_solid(&line, color)?;
+        }
+        Ok(())
+    }
+}
+
+/// SegmentShape::FlatとRoundedの、セグメントの中央の長方形
+#[derive(Debug, Clone, Copy)]
+enum SegmentBody {
+    Sharp(Rectangle),
+    Rounded(RoundedRectangle),
+}
+
+impl ContainsPoint for SegmentBody {
+    fn contains(&self, point: Point) -> bool {
+        match self {
+            SegmentBody::Sharp(rect) => rect.contains(point),
+            SegmentBody::Rounded(rect) => rect.contains(point),
+        }
+    }
+}
+
+/// shapeの内側の点を描画せずに捨てる、DrawTargetのラッパー
+///
+/// 丸い端の円のうち、中央の長方形と重なる部分を描かないために使う。
+struct Excluding<'a, D, S> {
+    target: &'a mut D,
+    shape: S,
+}
+
+impl<'a, D, S> Excluding<'a, D, S> {
+    fn new(target: &'a mut D, shape: S) -> Self {
+        Self { target, shape }
+    }
+}
+
+impl<D: Dimensions, S> Dimensions for Excluding<'_, D, S> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget, S: ContainsPoint> DrawTarget for Excluding<'_, D, S> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let shape = &self.shape;
+        self.target
+            .draw_iter(pixels.into_iter().filter(|Pixel(p, _)| !shape.contains(*p)))
+    }
+}
+
+/// 描画せずに、描画されるピクセルの範囲だけを記録するDrawTarget。bounding_rect用。
+struct BoundsTarget<C> {
+    /// 描画されたピクセルの左上と右下
+    corners: Option<(Point, Point)>,
+    color: PhantomData<C>,
+}
+
+impl<C> BoundsTarget<C> {
+    fn new() -> Self {
+        Self {
+            corners: None,
+            color: PhantomData,
+        }
+    }
+
+    fn rect(&self) -> Option<Rectangle> {
+        self.corners
+            .map(|(min, max)| Rectangle::with_corners(min, max))
+    }
+}
+
+impl<C> Dimensions for BoundsTarget<C> {
+    fn bounding_box(&self) -> Rectangle {
+        // 何も捨てないよう、十分に広い範囲とする。
+        Rectangle::new(
+            Point::new(-(1 << 29), -(1 << 29)),
+            Size::new(1 << 30, 1 << 30),
+        )
+    }
+}
+
+impl<C: PixelColor> DrawTarget for BoundsTarget<C> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, _) in pixels {
+            self.corners = Some(match self.corners {
+                Some((min, max)) => (min.component_min(p), max.component_max(p)),
+                None => (p, p),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// valueの平方根を、切り捨てた整数で返す。
+fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    // ニュートン法。初期値を真の値以上にしておけば、単調に減って収束する。
+    let mut x = value;
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// 長さlengthの区間を、長さtotalの区間の中央に置くときの開始位置。
+/// 余りが奇数の場合は、四捨五入する。
+fn centered_offset(total: u32, length: u32) -> i32 {
+    let rest = total as i64 - length as i64;
+    // 負の場合も、0から遠い方へ丸める
+    let half = (rest.abs() + 1) / 2;
+    (if rest < 0 { -half } else { half }) as i32
+}
+
+/// posから、右へdxだけ進めた位置。座標の範囲を超える場合は、範囲の端で止める。
+fn offset_x(pos: Point, dx: u32) -> Point {
+    let dx = dx.min(i32::MAX as u32) as i32;
+    Point::new(pos.x.saturating_add(dx), pos.y)
+}
+
+/// 二つの色を混ぜる関数。第3引数は、第1引数の色の割合(0〜255)
+type MixFn<C> = fn(C, C, u8) -> C;
+
+/// fgとbgを、alpha(0〜255、255でfgのみ)の割合で混ぜる。
+fn mix_colors<C>(fg: C, bg: C, alpha: u8) -> C
+where
+    C: From<Rgb888> + Into<Rgb888>,
+{
+    let fg: Rgb888 = fg.into();
+    let bg: Rgb888 = bg.into();
+    let alpha = alpha as u16;
+    let ch = |f: u8, b: u8| ((f as u16 * alpha + b as u16 * (255 - alpha) + 127) / 255) as u8;
+    Rgb888::new(ch(fg.r(), bg.r()), ch(fg.g(), bg.g()), ch(fg.b(), bg.b())).into()
+}
+
+impl<C: PixelColor> CharacterStyle for Font7Seg<C> {
+    type Color = C;
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        if let Some(color) = text_color {
+            self.text_color = color;
+        }
+    }
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+}
+
+impl<C: PixelColor> TextRenderer for Font7Seg<C> {
+    type Color = C;
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_text(text, pos, baseline, None, target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // 文字間の間隔は、空白と空白の間にだけ入れる。draw_textやmeasure_stringと同じ幅。
+        let advance = self
+            .size
+            .width
+            .saturating_mul(width)
+            .saturating_add(self.fraction_width(width))
+            .saturating_add(width.saturating_sub(1).saturating_mul(self.char_spacing));
+        let top_left = self.line_top_left(pos, baseline, advance);
+        if self.fits_coordinates(top_left, advance) {
+            self.fill_gap(top_left, advance, target)?;
+        }
+        Ok(offset_x(Point::new(top_left.x, pos.y), advance))
+    }
+
+    fn measure_string(&self, text: &str, pos: Point, baseline: Baseline) -> TextMetrics {
+        // 計測だけ"1"を狭くする場合は、幅を比例配置で求める。
+        let font = if self.measure_narrow_one {
+            self.with_proportional(true)
+        } else {
+            *self
+        };
+        let (advance, width) = font.line_width(font.glyphs(text).flatten());
+        let top_left = self.line_top_left(pos, baseline, width);
+        let next_position = self.next_position(Point::new(top_left.x, pos.y), advance);
+        let bounding_box = Rectangle::new(top_left, Size::new(width, self.size.height));
+        let bounding_box = transform::rotate_rect(self.rotation, bounding_box);
+        TextMetrics {
+            bounding_box,
+            next_position,
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        // TextのLineHeight::Percentは、行の高さに百分率を掛けるので、
+        // 初期値のPercent(100)で掛けても溢れない値に抑える。
+        self.line_height
+            .unwrap_or(self.size.height.saturating_add(self.line_spacing))
+            .min(u32::MAX / 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use core::cell::Cell;
+
+    std::thread_local! {
+        /// segment_polygonsを呼んだ回数。テストごとのスレッドで数える。
+        pub(crate) static SEGMENT_POLYGONS_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// fixed-pointフィーチャーの有無によらず、両方の実装を同じ値で比べる。
+    #[test]
+    fn scale_ceil_fixed_and_float_agree() {
+        let mut rates =
+            std::vec![0.0f32, 0.02, 0.05, 0.1, 0.15, 0.2, 0.25, 0.3, 0.33, 0.4, 0.5, 1.0];
+        rates.extend((0..=500).map(|i| i as f32 / 1000.0));
+        rates.extend([
+            f32::MIN_POSITIVE,
+            1e-40,
+            1e-30,
+            -0.1,
+            -0.0,
+            3.7,
+            1e10,
+            f32::INFINITY,
+            f32::NAN,
+        ]);
+        for rate in rates {
+            for value in (0..600).chain([1 << 20, (1 << 24) + 1, u32::MAX]) {
+                assert_eq!(
+                    scale_ceil_fixed(value, rate),
+                    scale_ceil_float(value, rate),
+                    "value={value} rate={rate}"
+                );
+            }
+        }
     }
 }
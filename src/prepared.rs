@@ -0,0 +1,123 @@
+//! セグメントの形を求めておき、繰り返しの描画を速くしたFont7Segです。
+//!
+//! Font7Segは、数字を一文字描画するごとに、セルの大きさとセグメントの太さの比率から、
+//! 各セグメントの多角形の頂点を求め直します。Font7Seg::prepared()で変換すると、
+//! 変換のときに一度だけ多角形を求めて保持し、描画ではそれを使い回します。
+use crate::eg;
+use crate::{Font7Seg, SegmentPolygons};
+
+use eg::pixelcolor::PixelColor;
+use eg::prelude::*;
+use eg::text::renderer::{CharacterStyle, TextMetrics, TextRenderer};
+use eg::text::Baseline;
+
+/// 各セグメントの頂点を求めておいた、7セグメントLED風フォント
+///
+/// 描画結果は、元のFont7Segと同じです。大きさや比率などは変更できません。
+#[derive(Debug, Clone, Copy)]
+pub struct PreparedFont7Seg<C> {
+    font: Font7Seg<C>,
+    polygons: SegmentPolygons,
+}
+
+impl<C: PixelColor> PreparedFont7Seg<C> {
+    pub(crate) fn new(font: Font7Seg<C>) -> Self {
+        let size = font.inner_area(font.size).size;
+        let polygons = font.segment_polygons(size);
+        Self { font, polygons }
+    }
+
+    /// 現在の表示する数字のサイズを返します。
+    pub fn character_size(&self) -> Size {
+        self.font.character_size()
+    }
+
+    /// 元のフォントを返します。
+    pub fn font(&self) -> &Font7Seg<C> {
+        &self.font
+    }
+}
+
+impl<C: PixelColor> CharacterStyle for PreparedFont7Seg<C> {
+    type Color = C;
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.font.set_text_color(text_color);
+    }
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.font.set_background_color(background_color);
+    }
+}
+
+impl<C: PixelColor> TextRenderer for PreparedFont7Seg<C> {
+    type Color = C;
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.font
+            .draw_text(text, pos, baseline, Some(&self.polygons), target)
+            .map(|(next_pos, _)| next_pos)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        pos: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.font.draw_whitespace(width, pos, baseline, target)
+    }
+
+    fn measure_string(&self, text: &str, pos: Point, baseline: Baseline) -> TextMetrics {
+        self.font.measure_string(text, pos, baseline)
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.line_height()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::SEGMENT_POLYGONS_CALLS;
+    use core::cell::Cell;
+    use eg::mock_display::MockDisplay;
+    use eg::pixelcolor::BinaryColor;
+    use eg::text::Text;
+
+    /// textを描画する間に、segment_polygonsを呼んだ回数と、描画結果。
+    fn count_draw<S>(style: S, text: &str) -> (usize, MockDisplay<BinaryColor>)
+    where
+        S: TextRenderer<Color = BinaryColor>,
+    {
+        let mut display = MockDisplay::new();
+        SEGMENT_POLYGONS_CALLS.with(|calls| calls.set(0));
+        Text::with_baseline(text, Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+        (SEGMENT_POLYGONS_CALLS.with(Cell::get), display)
+    }
+
+    /// 求めておいた頂点を使うので、描画のたびに頂点を求め直さない。
+    #[test]
+    fn prepared_skips_polygon_computation() {
+        let font = Font7Seg::new(Size::new(8, 16), BinaryColor::On);
+        let prepared = font.prepared();
+        let (plain_calls, plain) = count_draw(font, "01234");
+        let (prepared_calls, drawn) = count_draw(prepared, "01234");
+        assert_eq!(plain_calls, 5);
+        assert_eq!(prepared_calls, 0);
+        drawn.assert_eq(&plain);
+    }
+}
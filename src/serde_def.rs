@@ -0,0 +1,258 @@
+//! serdeフィーチャーで、Font7Segをシリアライズ・デシリアライズするための定義です。
+//!
+//! Font7Segは、この影の構造体を経由して読み書きします。色を混ぜる関数は保存できないので、
+//! 読み込むときに、アンチエイリアスなどの設定から作り直します。読み込んだ比率や角度は、
+//! with_*メソッドと同じ範囲に丸め込みます。
+use crate::eg;
+use crate::{
+    clamp_rate, mix_colors, tan_approx, Font7Seg, Origin, PointPosition, PointShape, Rotation,
+    SegmentShape, SegmentStyle,
+};
+
+use eg::pixelcolor::{PixelColor, Rgb888};
+use eg::prelude::*;
+
+/// serde用の、Sizeの定義
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Size")]
+struct SizeDef {
+    width: u32,
+    height: u32,
+}
+
+/// Font7Segの、保存できる設定だけを持つ構造体。フィールドの名前は、Font7Segと同じ。
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct Font7SegDef<C> {
+    #[serde(with = "SizeDef")]
+    size: Size,
+    text_color: C,
+    background_color: Option<C>,
+    background_gradient: Option<(C, C)>,
+    off_color: Option<C>,
+    separator_color: Option<C>,
+    point_as_background: bool,
+    highlight: Option<C>,
+    scanlines: Option<C>,
+    debug_cells: Option<C>,
+    blank_leading_zeros: bool,
+    attached_point: bool,
+    antialiasing: bool,
+    brightness: Option<(u8, C)>,
+    opacity: u8,
+    width_fraction: u32,
+    height_fraction: i32,
+    char_spacing: u32,
+    line_spacing: u32,
+    line_height: Option<u32>,
+    slant: f32,
+    segment_tilt: f32,
+    mirror_x: bool,
+    rotation: Rotation,
+    origin: Origin,
+    proportional: bool,
+    measure_narrow_one: bool,
+    segment_shape: SegmentShape,
+    corner_radius: u32,
+    segment_style: SegmentStyle,
+    dot_segments: Option<(u32, u32)>,
+    point_position: PointPosition,
+    point_shape: PointShape,
+    segment_gap: u32,
+    seg_pats: [u8; 16],
+    line_width_rate: f32,
+    top_margin_rate: f32,
+    left_margin_rate: f32,
+    point_width_rate: f32,
+    sign_width_rate: f32,
+}
+
+impl<C: PixelColor> From<Font7Seg<C>> for Font7SegDef<C> {
+    fn from(font: Font7Seg<C>) -> Self {
+        Self {
+            size: font.size,
+            text_color: font.text_color,
+            background_color: font.background_color,
+            background_gradient: font.background_gradient,
+            off_color: font.off_color,
+            separator_color: font.separator_color,
+            point_as_background: font.point_as_background,
+            highlight: font.highlight,
+            scanlines: font.scanlines,
+            debug_cells: font.debug_cells,
+            blank_leading_zeros: font.blank_leading_zeros,
+            attached_point: font.attached_point,
+            antialiasing: font.antialiasing,
+            brightness: font.brightness,
+            opacity: font.opacity,
+            width_fraction: font.width_fraction,
+            height_fraction: font.height_fraction,
+            char_spacing: font.char_spacing,
+            line_spacing: font.line_spacing,
+            line_height: font.line_height,
+            slant: font.slant,
+            segment_tilt: font.segment_tilt,
+            mirror_x: font.mirror_x,
+            rotation: font.rotation,
+            origin: font.origin,
+            proportional: font.proportional,
+            measure_narrow_one: font.measure_narrow_one,
+            segment_shape: font.segment_shape,
+            corner_radius: font.corner_radius,
+            segment_style: font.segment_style,
+            dot_segments: font.dot_segments,
+            point_position: font.point_position,
+            point_shape: font.point_shape,
+            segment_gap: font.segment_gap,
+            seg_pats: font.seg_pats,
+            line_width_rate: font.line_width_rate,
+            top_margin_rate: font.top_margin_rate,
+            left_margin_rate: font.left_margin_rate,
+            point_width_rate: font.point_width_rate,
+            sign_width_rate: font.sign_width_rate,
+        }
+    }
+}
+
+impl<C> From<Font7SegDef<C>> for Font7Seg<C>
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    fn from(def: Font7SegDef<C>) -> Self {
+        // 色を混ぜる設定のどれかを使っていれば、with_*メソッドと同じく、混ぜる関数を戻す。
+        let blended = def.antialiasing
+            || def.brightness.is_some()
+            || def.opacity < 255
+            || def.background_gradient.is_some();
+        let mut seg_pats = def.seg_pats;
+        for pat in seg_pats.iter_mut() {
+            *pat &= 0b0111_1111;
+        }
+        Self {
+            size: def.size,
+            text_color: def.text_color,
+            background_color: def.background_color,
+            background_gradient: def.background_gradient,
+            off_color: def.off_color,
+            separator_color: def.separator_color,
+            point_as_background: def.point_as_background,
+            highlight: def.highlight,
+            scanlines: def.scanlines,
+            debug_cells: def.debug_cells,
+            blank_leading_zeros: def.blank_leading_zeros,
+            attached_point: def.attached_point,
+            antialiasing: def.antialiasing,
+            mix: if blended { Some(mix_colors::<C>) } else { None },
+            brightness: def.brightness,
+            opacity: def.opacity,
+            width_fraction: def.width_fraction.min(0xffff),
+            height_fraction: def.height_fraction.clamp(-0x8000, 0x8000),
+            char_spacing: def.char_spacing,
+            line_spacing: def.line_spacing,
+            line_height: def.line_height,
+            slant: clamp_shear(def.slant, 30.0),
+            segment_tilt: clamp_shear(def.segment_tilt, 15.0),
+            mirror_x: def.mirror_x,
+            rotation: def.rotation,
+            origin: def.origin,
+            proportional: def.proportional,
+            measure_narrow_one: def.measure_narrow_one,
+            segment_shape: def.segment_shape,
+            corner_radius: def.corner_radius,
+            segment_style: def.segment_style,
+            dot_segments: def
+                .dot_segments
+                .map(|(diameter, spacing)| (diameter.max(1), spacing)),
+            point_position: def.point_position,
+            point_shape: def.point_shape,
+            segment_gap: def.segment_gap,
+            seg_pats,
+            line_width_rate: clamp_rate(def.line_width_rate, 0.5),
+            top_margin_rate: clamp_rate(def.top_margin_rate, 0.25),
+            left_margin_rate: clamp_rate(def.left_margin_rate, 0.25),
+            point_width_rate: clamp_rate(def.point_width_rate, 0.5),
+            sign_width_rate: clamp_rate(def.sign_width_rate, 1.0),
+        }
+    }
+}
+
+/// 1行あたりのずれshearを、±max_degrees度の傾きの範囲に丸め込む。NaNは0.0とする。
+fn clamp_shear(shear: f32, max_degrees: f32) -> f32 {
+    let max = tan_approx(max_degrees.to_radians());
+    if shear.is_nan() {
+        0.0
+    } else {
+        shear.clamp(-max, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eg::mock_display::MockDisplay;
+    use eg::text::renderer::CharacterStyle;
+    use eg::text::{Baseline, Text};
+
+    fn round_trip(font: Font7Seg<Rgb888>) -> Font7Seg<Rgb888> {
+        Font7Seg::from(Font7SegDef::from(font))
+    }
+
+    fn draw(font: Font7Seg<Rgb888>) -> MockDisplay<Rgb888> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        Text::with_baseline("8.7", Point::zero(), font, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+        display
+    }
+
+    /// 読み込んだ後も、アンチエイリアスと明るさ、不透明度が効く。
+    #[test]
+    fn round_trip_keeps_blending() {
+        let mut font = Font7Seg::new(Size::new(10, 20), Rgb888::WHITE)
+            .with_slant(10.0)
+            .with_antialiasing(true)
+            .with_brightness(0.5)
+            .with_opacity(128);
+        font.set_background_color(Some(Rgb888::BLUE));
+        let restored = round_trip(font);
+        assert!(restored.mix.is_some());
+        assert_eq!(restored.opacity, 128);
+        draw(restored).assert_eq(&draw(font));
+        // 混ぜる設定を使わないフォントは、混ぜる関数も持たない
+        assert!(round_trip(Font7Seg::new(Size::new(10, 20), Rgb888::WHITE))
+            .mix
+            .is_none());
+    }
+
+    /// 範囲外の比率や角度、NaNは、with_*メソッドと同じく丸め込む。
+    #[test]
+    fn invalid_values_are_clamped() {
+        let mut def = Font7SegDef::from(Font7Seg::new(Size::new(10, 20), Rgb888::WHITE));
+        def.line_width_rate = f32::NAN;
+        def.top_margin_rate = 2.0;
+        def.left_margin_rate = -1.0;
+        def.point_width_rate = f32::INFINITY;
+        def.sign_width_rate = f32::NAN;
+        def.slant = f32::NAN;
+        def.segment_tilt = 100.0;
+        def.width_fraction = u32::MAX;
+        def.height_fraction = i32::MIN;
+        def.dot_segments = Some((0, 2));
+        def.seg_pats = [0xff; 16];
+        let font = Font7Seg::from(def);
+        assert_eq!(font.line_width_rate, 0.0);
+        assert_eq!(font.top_margin_rate, 0.25);
+        assert_eq!(font.left_margin_rate, 0.0);
+        assert_eq!(font.point_width_rate, 0.5);
+        assert_eq!(font.sign_width_rate, 0.0);
+        assert_eq!(font.slant, 0.0);
+        let tilted = Font7Seg::new(Size::new(10, 20), Rgb888::WHITE).with_segment_tilt(15.0);
+        assert_eq!(font.segment_tilt, tilted.segment_tilt);
+        assert_eq!(font.width_fraction, 0xffff);
+        assert_eq!(font.height_fraction, -0x8000);
+        assert_eq!(font.dot_segments, Some((1, 2)));
+        assert!(font.seg_pats.iter().all(|&pat| pat == 0x7f));
+        // 丸め込んだ値で、パニックせずに描画できる
+        draw(font);
+    }
+}
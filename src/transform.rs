@@ -0,0 +1,230 @@
+//! 一文字分のセルや文字列の範囲の座標を変換して描画する、DrawTargetのラッパーです。
+use crate::{eg, Rotation};
+
+#[cfg_attr(test, allow(unused_imports))]
+use num_traits::float::FloatCore;
+
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+
+/// セル内の座標の変換
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CellMap {
+    /// セルの大きさ
+    pub(crate) size: Size,
+    /// 1行あたりの水平方向のずれ。正で右に、負で左に傾く。
+    pub(crate) shear: f32,
+    /// 左右反転するか。傾けた後、はみ出す部分も含めたセルの幅の中で反転する。
+    pub(crate) mirror_x: bool,
+    /// 反転するときに、はみ出す部分に加える幅。字形自体がセルからはみ出す場合に使う。
+    pub(crate) extra_width: u32,
+}
+
+impl CellMap {
+    /// 座標を変換しないか
+    fn is_identity(&self) -> bool {
+        self.shear == 0.0 && !self.mirror_x
+    }
+
+    /// 行yの、水平方向のずれ。常に0以上で、最大でもoverhang()となる。
+    fn shift(&self, y: i32) -> i32 {
+        let rows = if self.shear >= 0.0 {
+            self.size.height as i32 - 1 - y
+        } else {
+            y
+        };
+        (self.shear.abs() * rows as f32).round() as i32
+    }
+
+    fn map(&self, p: Point) -> Point {
+        let x = p.x + self.shift(p.y);
+        if self.mirror_x {
+            let width = self.size.width + overhang(self.shear, self.size.height) + self.extra_width;
+            Point::new(width as i32 - 1 - x, p.y)
+        } else {
+            Point::new(x, p.y)
+        }
+    }
+}
+
+/// セル内の座標を変換して、元のDrawTargetに描画するラッパー
+///
+/// 描画側からは、CellMapの大きさのセルに見える。各点は、行ごとに水平方向へずらして
+/// (斜体のように)、また必要なら左右反転して、元のDrawTargetのoriginの位置を
+/// 左上隅とするセルに描画される。元のDrawTargetの範囲外の点は、描画せずに捨てる。
+pub(crate) struct Transformed<'a, D> {
+    target: &'a mut D,
+    map: CellMap,
+    origin: Point,
+    clip: Rectangle,
+}
+
+impl<'a, D: DrawTarget> Transformed<'a, D> {
+    pub(crate) fn new(target: &'a mut D, map: CellMap, origin: Point) -> Self {
+        let clip = target.bounding_box();
+        Self {
+            target,
+            map,
+            origin,
+            clip,
+        }
+    }
+
+    /// セル内の点の、元のDrawTargetでの位置
+    fn to_target(&self, p: Point) -> Point {
+        let p = if self.map.is_identity() {
+            p
+        } else {
+            self.map.map(p)
+        };
+        p + self.origin
+    }
+}
+
+/// 1行あたりのずれがshearのとき、高さheightのセルがはみ出す幅
+pub(crate) fn overhang(shear: f32, height: u32) -> u32 {
+    (shear.abs() * height.saturating_sub(1) as f32).round() as u32
+}
+
+impl<D: DrawTarget> Dimensions for Transformed<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.map.size)
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for Transformed<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (map, origin, clip) = (self.map, self.origin, self.clip);
+        let identity = map.is_identity();
+        let pixels = pixels
+            .into_iter()
+            .map(|Pixel(p, c)| {
+                let p = if identity { p } else { map.map(p) };
+                Pixel(p + origin, c)
+            })
+            .filter(|Pixel(p, _)| clip.contains(*p));
+        self.target.draw_iter(pixels)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.map.is_identity() {
+            let area = area.translate(self.origin).intersection(&self.clip);
+            return self.target.fill_solid(&area, color);
+        }
+        // 行ごとにずらして塗る。draw_iterと同じく、セルの外にかかる部分も、ずらして描く。
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        let right = area.top_left.x + area.size.width as i32 - 1;
+        for row in area.rows() {
+            let left = self.to_target(Point::new(area.top_left.x, row));
+            let right = self.to_target(Point::new(right, row));
+            let top_left = left.component_min(right);
+            let line = Rectangle::new(top_left, Size::new(area.size.width, 1));
+            self.target
+                .fill_solid(&line.intersection(&self.clip), color)?;
+        }
+        Ok(())
+    }
+}
+
+/// 長方形areaを、その左上隅を基準に回転した範囲。90度と270度では、幅と高さが入れ替わる。
+pub(crate) fn rotate_rect(rotation: Rotation, area: Rectangle) -> Rectangle {
+    match rotation {
+        Rotation::Deg0 | Rotation::Deg180 => area,
+        Rotation::Deg90 | Rotation::Deg270 => {
+            Rectangle::new(area.top_left, Size::new(area.size.height, area.size.width))
+        }
+    }
+}
+
+/// 長方形area内の点pを、時計回りに回転した位置。回転後の点は、rotate_rect()の範囲に入る。
+pub(crate) fn rotate_point(rotation: Rotation, area: Rectangle, p: Point) -> Point {
+    let Size { width, height } = area.size;
+    let (w, h) = (width as i32, height as i32);
+    let r = p - area.top_left;
+    let r = match rotation {
+        Rotation::Deg0 => r,
+        Rotation::Deg90 => Point::new(h - 1 - r.y, r.x),
+        Rotation::Deg180 => Point::new(w - 1 - r.x, h - 1 - r.y),
+        Rotation::Deg270 => Point::new(r.y, w - 1 - r.x),
+    };
+    r + area.top_left
+}
+
+/// 長方形areaの中身を、時計回りに回転して、元のDrawTargetに描画するラッパー
+///
+/// 描画側からは、areaの範囲に見える。回転した結果は、rotate_rect()の範囲に描画される。
+/// Deg0の場合は、何も変換せずに、元のDrawTargetに描画する。
+pub(crate) struct Rotated<'a, D> {
+    target: &'a mut D,
+    rotation: Rotation,
+    area: Rectangle,
+    clip: Rectangle,
+}
+
+impl<'a, D: DrawTarget> Rotated<'a, D> {
+    pub(crate) fn new(target: &'a mut D, rotation: Rotation, area: Rectangle) -> Self {
+        let clip = target.bounding_box();
+        Self {
+            target,
+            rotation,
+            area,
+            clip,
+        }
+    }
+}
+
+impl<D: DrawTarget> Dimensions for Rotated<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        match self.rotation {
+            Rotation::Deg0 => self.clip,
+            _ => self.area,
+        }
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for Rotated<'_, D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (rotation, area, clip) = (self.rotation, self.area, self.clip);
+        if rotation == Rotation::Deg0 {
+            return self.target.draw_iter(pixels);
+        }
+        let pixels = pixels
+            .into_iter()
+            .filter(|Pixel(p, _)| area.contains(*p))
+            .map(|Pixel(p, c)| Pixel(rotate_point(rotation, area, p), c))
+            .filter(|Pixel(p, _)| clip.contains(*p));
+        self.target.draw_iter(pixels)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.rotation == Rotation::Deg0 {
+            return self.target.fill_solid(area, color);
+        }
+        let area = area.intersection(&self.area);
+        match area.bottom_right() {
+            Some(bottom_right) => {
+                let rotated = Rectangle::with_corners(
+                    rotate_point(self.rotation, self.area, area.top_left),
+                    rotate_point(self.rotation, self.area, bottom_right),
+                );
+                self.target
+                    .fill_solid(&rotated.intersection(&self.clip), color)
+            }
+            None => Ok(()),
+        }
+    }
+}